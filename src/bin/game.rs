@@ -21,7 +21,9 @@ use amethyst::{
 use clap::{App, Arg};
 use gilrs::*;
 use log::*;
+use nk_data::*;
 use nk_ecs::*;
+use nk_loader::*;
 use nk_state::*;
 use std::{collections::HashMap, sync::*, time::Duration};
 
@@ -42,22 +44,85 @@ fn main() -> amethyst::Result<()> {
                 .help("Specifies which map to open as default")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("seed")
+                .short("s")
+                .long("seed")
+                .value_name("SEED")
+                .help("Seeds the match random number generator, for reproducing matches")
+                .takes_value(true)
+                .validator(|s| {
+                    s.parse::<u64>()
+                        .map(|_| ())
+                        .map_err(|_| String::from("the seed must be an unsigned 64 bit integer"))
+                }),
+        )
+        .arg(
+            Arg::with_name("record")
+                .short("r")
+                .long("record")
+                .value_name("FILE")
+                .help("Records the next match inputs into a replay file")
+                .takes_value(true)
+                .conflicts_with("replay"),
+        )
+        .arg(
+            Arg::with_name("replay")
+                .long("replay")
+                .value_name("FILE")
+                .help("Plays back a replay file recorded with --record")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("dev")
+                .long("dev")
+                .help("Reloads data and map files when they change on disk"),
+        )
         .get_matches();
 
+    // Already checked by the argument validator.
+    let seed = MatchSeed(matches.value_of("seed").and_then(|s| s.parse::<u64>().ok()));
+
+    let replay = if let Some(path) = matches.value_of("replay") {
+        ReplayMode::Playing {
+            replay: match load_replay(path) {
+                Ok(replay) => replay,
+                Err(e) => {
+                    error!("{}", e);
+                    std::process::exit(1);
+                }
+            },
+            frame: 0,
+        }
+    } else if let Some(path) = matches.value_of("record") {
+        ReplayMode::Recording {
+            path: path.to_owned(),
+            replay: Replay::default(),
+        }
+    } else {
+        ReplayMode::Off
+    };
+
     std::env::set_var("RUST_LOG", "gfx_device_gl=warn");
 
     let channel = Arc::new(Mutex::new(EventChannel::<ev::Event>::new()));
+    let controllers = Arc::new(Mutex::new(HashMap::<usize, Controller>::new()));
     {
         let channel = channel.clone();
+        let controllers = controllers.clone();
         std::thread::spawn(move || {
             let mut gilrs = Gilrs::new().unwrap();
-            for (_id, gamepad) in gilrs.gamepads() {
+            for (id, gamepad) in gilrs.gamepads() {
                 info!(
                     "{} (id:{}) is {:?}",
                     gamepad.os_name(),
                     gamepad.id(),
                     gamepad.power_info()
                 );
+                // Pads plugged before starting never send a Connected event.
+                let mut controller = Controller::new(id);
+                controller.active = true;
+                controllers.lock().unwrap().insert(id, controller);
             }
             loop {
                 while let Some(event) = gilrs.next_event() {
@@ -93,7 +158,7 @@ fn main() -> amethyst::Result<()> {
             InputBundle::<String, String>::new().with_bindings_from_file(&key_bindings_path)?,
         )?
         .with_bundle(GameBundle)?
-        .with_bundle(TransformBundle::new().with_dep(&["xto_movement"]))?
+        .with_bundle(TransformBundle::new())?
         .with_bundle(UiBundle::<String, String>::new())?
         .with_bundle(AudioBundle::new(|_: &mut NoMusic| None))?
         //.with(Processor::<Source>::new(), "source_processor", &[])
@@ -105,11 +170,12 @@ fn main() -> amethyst::Result<()> {
 
     let game_state = Load::default();
 
-    let controllers: HashMap<usize, Controller> = HashMap::new();
     let mut game = Application::build(assets_directory, game_state)?
-        .with_resource(Arc::new(Mutex::new(controllers)))
+        .with_resource(controllers)
         .with_resource(channel)
         .with_resource(matches)
+        .with_resource(seed)
+        .with_resource(replay)
         .with_resource(NoMusic)
         .with_frame_limit(
             FrameRateLimitStrategy::SleepAndYield(Duration::from_millis(2)),
@@ -2,10 +2,14 @@
 #![feature(exclusive_range_pattern)]
 #![feature(type_ascription)]
 
-use clap::{App, Arg, SubCommand};
+use amethyst::utils::application_root_dir;
+use clap::{App, Arg, ArgMatches, SubCommand};
 use nk_data::*;
+use nk_loader::*;
+use nk_state::*;
 use ron::de::from_reader;
-use std::fs::File;
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fs::File, iter, path::Path};
 
 fn main() {
     let map_sub = SubCommand::with_name("map")
@@ -84,6 +88,74 @@ fn main() {
                 .help("Specifies the file from which to try and parse player definitions from")
                 .takes_value(true)
                 .multiple(true),
+        )
+        .arg(
+            Arg::with_name("i18n")
+                .long("i18n")
+                .value_name("RON_FILE")
+                .help("Specifies the translation file every key must have entries in")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("textures")
+                .long("textures")
+                .value_name("DIR")
+                .help("Specifies the texture folder holding the sprite index files")
+                .takes_value(true),
+        );
+
+    let sim_sub = SubCommand::with_name("sim")
+        .version("0.1.0")
+        .author("Tiago Nascimento <xtheosirian@gmail.com>")
+        .about("Runs a match without window, for testing gameplay changes")
+        .arg(
+            Arg::with_name("map")
+                .short("m")
+                .long("map")
+                .value_name("MAP_FILE")
+                .help("Specifies which map to run the match on")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .short("s")
+                .long("seed")
+                .value_name("SEED")
+                .help("Seeds the match random number generator")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("generation")
+                .short("g")
+                .long("generation")
+                .value_name("PROFILE")
+                .help("Overrides the order generation profile chosen by the map")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("duration")
+                .short("d")
+                .long("duration")
+                .value_name("SECONDS")
+                .help("Length of the match, defaults to 300 seconds")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("timestep")
+                .short("t")
+                .long("timestep")
+                .value_name("SECONDS")
+                .help("Length of every simulation step, defaults to 1/60 seconds")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("report")
+                .short("r")
+                .long("report")
+                .value_name("REPORT_FILE")
+                .help("Writes the match statistics to this file when the match ends")
+                .takes_value(true),
         );
 
     let matches = App::new("Naisu Kurimu")
@@ -92,108 +164,378 @@ fn main() {
         .about("Ferramentas do Jogo Naisu Kurimu")
         .subcommand(map_sub)
         .subcommand(data_sub)
+        .subcommand(sim_sub)
         .get_matches();
 
-    if let Some(_map_matches) = matches.subcommand_matches("map") {
-        println!("[ERROR] map not implemented!");
-    }
-
-    if let Some(data_matches) = matches.subcommand_matches("data") {
-        if let Some(flavors) = data_matches.values_of("flavor") {
-            flavors.into_iter().for_each(move |path| {
+    if let Some(map_matches) = matches.subcommand_matches("map") {
+        let mut failed = false;
+        if let Some(maps) = map_matches.values_of("map") {
+            maps.into_iter().for_each(|path| {
                 println!("[INFO] file '{}'", path);
-                if match from_reader(File::open(&path).expect("[ERROR] failed to open!")):
-                    Result<Vec<_>, _>
-                {
-                    Ok(x) => {
-                        let mut res = false;
-                        if x.iter().any(|f: &FlavorDef| f.key.is_empty()) {
-                            res = true;
-                            println!("[WARNING] flavors with empty keys detected!");
+                let map: MapDefinition =
+                    match from_reader(File::open(&path).expect("[ERROR] failed to open!")) {
+                        Ok(x) => x,
+                        Err(e) => {
+                            println!("[ERROR] could not parse file as map definition: {}", e);
+                            failed = true;
+                            return;
                         }
-                        res
-                    }
-                    Err(e) => {
-                        println!("[ERROR] could not parse file as flavor definition: {}", e);
-                        return;
-                    }
-                } {
-                    println!("[WARNING] file has been correctly parsed but has problems!");
-                } else {
+                    };
+                println!("{}", map.render_ascii());
+                let errors = map.validate();
+                if errors.is_empty() {
                     println!("[INFO] file is valid!");
+                } else {
+                    errors.iter().for_each(|e| println!("[ERROR] {}", e));
+                    failed = true;
                 }
             });
+        } else {
+            println!("[ERROR] no map file given, use --map!");
+            failed = true;
+        }
+        if failed {
+            std::process::exit(1);
         }
+    }
 
-        if let Some(preparations) = data_matches.values_of("preparation") {
-            preparations.into_iter().for_each(move |path| {
-                println!("[INFO] file '{}'", path);
-                if match from_reader(File::open(&path).expect("[ERROR] failed to open!")):
-                    Result<Vec<_>, _>
-                {
-                    Ok(x) => {
-                        let mut res = false;
-                        if x.iter().any(|p: &PreparationDef| p.key.is_empty()) {
-                            res = true;
-                            println!("[WARNING] preparations with empty keys detected!");
-                        }
-                        res
-                    }
-                    Err(e) => {
-                        println!(
-                            "[ERROR] could not parse file as preparation definition: {}",
-                            e
-                        );
-                        return;
-                    }
-                } {
-                    println!("[WARNING] file has been correctly parsed but has problems!");
-                } else {
-                    println!("[INFO] file is valid!");
+    if let Some(data_matches) = matches.subcommand_matches("data") {
+        let mut report = Report::default();
+
+        let flavors: Vec<FlavorDef> = parse_files(&mut report, data_matches, "flavor", "flavors");
+        let preparations: Vec<PreparationDef> =
+            parse_files(&mut report, data_matches, "preparation", "preparations");
+        let toppings: Vec<ToppingDef> =
+            parse_files(&mut report, data_matches, "topping", "toppings");
+        let hissatsu: Vec<HissatsuDef> =
+            parse_files(&mut report, data_matches, "hissatsu", "hissatsu");
+        let houses: Vec<HouseDef> = parse_files(&mut report, data_matches, "house", "houses");
+        let teams: Vec<TeamDef> = parse_files(&mut report, data_matches, "team", "teams");
+        let players: Vec<PlayerDef> = parse_files(&mut report, data_matches, "player", "players");
+
+        let i18n: HashMap<String, String> = {
+            let path = data_matches
+                .value_of("i18n")
+                .map(|p| p.to_owned())
+                .unwrap_or_else(|| format!("{}/assets/i18n/en_us.ron", application_root_dir()));
+            match File::open(&path).map(from_reader) {
+                Ok(Ok(x)) => x,
+                Ok(Err(e)) => {
+                    report.error(format!("could not parse i18n file '{}': {}", path, e));
+                    HashMap::new()
+                }
+                Err(e) => {
+                    report.error(format!("could not open i18n file '{}': {}", path, e));
+                    HashMap::new()
                 }
+            }
+        };
+        let textures = data_matches
+            .value_of("textures")
+            .map(|p| p.to_owned())
+            .unwrap_or_else(|| format!("{}/assets/texture", application_root_dir()));
+
+        check_defs(&mut report, "flavor", &flavors, &i18n, &textures);
+        check_defs(&mut report, "preparation", &preparations, &i18n, &textures);
+        check_defs(&mut report, "topping", &toppings, &i18n, &textures);
+        check_defs(&mut report, "hissatsu", &hissatsu, &i18n, &textures);
+        check_defs(&mut report, "house", &houses, &i18n, &textures);
+        check_defs(&mut report, "team", &teams, &i18n, &textures);
+        check_defs(&mut report, "player", &players, &i18n, &textures);
+
+        preparations.iter().for_each(|p| {
+            p.validate()
+                .iter()
+                .for_each(|e| report.error(format!("preparation '{}' {}", p.key, e)))
+        });
+
+        players.iter().for_each(|p| {
+            let mut refs = Vec::new();
+            refs.extend(
+                iter::once(&p.primary_flavor)
+                    .chain(p.secondary_flavors.iter())
+                    .filter(|f| !flavors.iter().any(|x| x.index == **f))
+                    .map(|f| format!("flavor {}", f.0)),
+            );
+            refs.extend(
+                p.preparations
+                    .iter()
+                    .filter(|i| !preparations.iter().any(|x| x.index == **i))
+                    .map(|i| format!("preparation {}", i.0)),
+            );
+            refs.extend(
+                p.toppings
+                    .iter()
+                    .filter(|i| !toppings.iter().any(|x| x.index == **i))
+                    .map(|i| format!("topping {}", i.0)),
+            );
+            refs.extend(
+                p.hissatsu
+                    .iter()
+                    .filter(|i| !hissatsu.iter().any(|x| x.index == **i))
+                    .map(|i| format!("hissatsu {}", i.0)),
+            );
+            refs.extend(
+                p.team
+                    .iter()
+                    .filter(|i| !teams.iter().any(|x| x.index == **i))
+                    .map(|i| format!("team {}", i.0)),
+            );
+            refs.extend(
+                p.house
+                    .iter()
+                    .filter(|i| !houses.iter().any(|x| x.index == **i))
+                    .map(|i| format!("house {}", i.0)),
+            );
+            refs.into_iter().for_each(|r| {
+                report.error(format!("player '{}' references missing {}", p.name, r))
             });
-        }
+        });
 
-        if let Some(toppings) = data_matches.values_of("topping") {
-            toppings.into_iter().for_each(move |path| {
-                println!("[INFO] file '{}'", path);
-                if match from_reader(File::open(&path).expect("[ERROR] failed to open!")):
-                    Result<Vec<_>, _>
-                {
-                    Ok(x) => {
-                        let mut res = false;
-                        if x.iter().any(|t: &ToppingDef| t.key.is_empty()) {
-                            res = true;
-                            println!("[WARNING] toppings with empty keys detected!");
-                        }
-                        res
-                    }
-                    Err(e) => {
-                        println!("[ERROR] could not parse file as topping definition: {}", e);
-                        return;
-                    }
-                } {
-                    println!("[WARNING] file has been correctly parsed but has problems!");
-                } else {
-                    println!("[INFO] file is valid!");
+        report.finish();
+    }
+
+    if let Some(sim_matches) = matches.subcommand_matches("sim") {
+        let path = sim_matches.value_of("map").unwrap();
+        let map: MapDefinition =
+            match from_reader(File::open(&path).expect("[ERROR] failed to open!")) {
+                Ok(x) => x,
+                Err(e) => {
+                    println!("[ERROR] could not parse file as map definition: {}", e);
+                    std::process::exit(1);
                 }
-            });
+            };
+        let errors = map.validate();
+        if !errors.is_empty() {
+            errors.iter().for_each(|e| println!("[ERROR] {}", e));
+            std::process::exit(1);
         }
+        let seed = sim_matches
+            .value_of("seed")
+            .map(|s| s.parse::<u64>().expect("[ERROR] seed must be an unsigned integer!"));
+        let duration = sim_matches
+            .value_of("duration")
+            .map(|s| s.parse::<f32>().expect("[ERROR] duration must be a number!"))
+            .unwrap_or(5.0 * 60.0);
+        let timestep = sim_matches
+            .value_of("timestep")
+            .map(|s| s.parse::<f32>().expect("[ERROR] timestep must be a number!"))
+            .unwrap_or(TICK_DELTA);
 
-        if let Some(_hissatsu) = data_matches.values_of("hissatsu") {
-            println!("[ERROR] hissatsu not implemented!");
+        let defs = match load_game_data() {
+            Ok(defs) => defs,
+            Err(errors) => {
+                errors.iter().for_each(|e| println!("[ERROR] {}", e));
+                std::process::exit(1);
+            }
+        };
+        let flavors = defs
+            .flavors()
+            .take(4)
+            .fold(Vec::with_capacity(8), |mut acc, f| {
+                acc.push(f.index.clone());
+                acc.push(f.index.clone());
+                acc
+            });
+        let preparation = defs.preparations().next().unwrap().index.clone();
+        let topping = defs.toppings().next().unwrap().index.clone();
+
+        let mut simulation = SimulationSetup::with_map(map)
+            .with_flavors(flavors)
+            .with_preparations(iter::repeat(preparation).take(4).collect())
+            .with_toppings(iter::repeat(topping).take(4).collect())
+            .with_generation(sim_matches.value_of("generation").map(|g| g.to_owned()))
+            .with_seed(seed)
+            .with_duration(duration)
+            .with_timestep(timestep)
+            .build(defs);
+        let steps = simulation.run_to_end();
+        println!("[INFO] match finished after {} steps", steps);
+        simulation
+            .scores()
+            .iter()
+            .enumerate()
+            .for_each(|(i, s)| println!("[INFO] team {} scored {}", i, s));
+        match simulation.results().winner() {
+            Some(team) => println!("[INFO] team {} wins", team),
+            None => println!("[INFO] draw"),
+        }
+        if let Some(path) = sim_matches.value_of("report") {
+            println!("[INFO] writing match report to {}", path);
+            save_match_report(path, &simulation.report());
         }
+    }
+}
 
-        if let Some(_houses) = data_matches.values_of("house") {
-            println!("[ERROR] house not implemented!");
+/// Definitions that can be validated by the data subcommand.
+trait DataDef {
+    fn index(&self) -> usize;
+    fn key(&self) -> &str;
+}
+
+macro_rules! impl_data_def {
+    ($def:ty, $key:ident) => {
+        impl DataDef for $def {
+            fn index(&self) -> usize {
+                self.index.0
+            }
+
+            fn key(&self) -> &str {
+                &self.$key
+            }
         }
+    };
+}
+
+impl_data_def!(FlavorDef, key);
+impl_data_def!(PreparationDef, key);
+impl_data_def!(ToppingDef, key);
+impl_data_def!(HissatsuDef, key);
+impl_data_def!(HouseDef, key);
+impl_data_def!(TeamDef, key);
+impl_data_def!(PlayerDef, name);
+
+/// Kinds of definition that are never drawn with sprites of their own.
+const UNSPRITED: &[&str] = &["hissatsu", "house", "team"];
+
+/// Known gaps in the assets, reported as warnings until they are made so new gaps still fail
+/// the check. Remove entries as the text and sprites land.
+const PENDING_TEXT: &[&str] = &["preparation", "topping", "hissatsu", "team", "player"];
+const PENDING_SPRITES: &[(&str, &str)] = &[
+    ("flavor", "neapolitan"),
+    ("flavor", "rainbow"),
+    ("flavor", "balloon"),
+    ("flavor", "spumoni"),
+];
 
-        if let Some(_teams) = data_matches.values_of("team") {
-            println!("[ERROR] team not implemented!");
+#[derive(Default)]
+struct Report {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn error(&mut self, msg: String) {
+        println!("[ERROR] {}", msg);
+        self.errors += 1;
+    }
+
+    fn warning(&mut self, msg: String) {
+        println!("[WARNING] {}", msg);
+        self.warnings += 1;
+    }
+
+    /// Exits with an error code when any error was reported.
+    fn finish(&self) {
+        println!(
+            "[INFO] {} errors and {} warnings found!",
+            self.errors, self.warnings
+        );
+        if self.errors > 0 {
+            std::process::exit(1);
         }
+    }
+}
+
+/// Parses every file given to `arg`, falling back to the game's own data file when none is given
+/// so references can still be resolved.
+fn parse_files<T: DeserializeOwned>(
+    report: &mut Report,
+    matches: &ArgMatches,
+    arg: &str,
+    default: &str,
+) -> Vec<T> {
+    let paths = match matches.values_of(arg) {
+        Some(paths) => paths.map(|p| p.to_owned()).collect(),
+        None => vec![format!(
+            "{}/assets/data/{}.ron",
+            application_root_dir(),
+            default
+        )],
+    };
 
-        if let Some(_players) = data_matches.values_of("player") {
-            println!("[ERROR] player not implemented!");
+    paths
+        .into_iter()
+        .flat_map(|path| {
+            println!("[INFO] file '{}'", path);
+            match File::open(&path).map(from_reader) {
+                Ok(Ok(x)) => x,
+                Ok(Err(e)) => {
+                    report.error(format!("could not parse file as {} definition: {}", arg, e));
+                    Vec::new()
+                }
+                Err(e) => {
+                    report.error(format!("could not open file: {}", e));
+                    Vec::new()
+                }
+            }
+        })
+        .collect()
+}
+
+fn check_defs<T: DataDef>(
+    report: &mut Report,
+    what: &str,
+    defs: &[T],
+    i18n: &HashMap<String, String>,
+    textures: &str,
+) {
+    let mut indices = defs.iter().map(|d| d.index()).collect::<Vec<_>>();
+    indices.sort_unstable();
+    for w in indices.windows(2) {
+        if w[0] == w[1] {
+            report.error(format!("{} index {} is used more than once", what, w[0]));
         }
     }
+    indices.dedup();
+    if let Some(missing) = (0..indices.len()).find(|i| indices[*i] != *i) {
+        report.error(format!(
+            "{} indices are not contiguous, index {} is missing",
+            what, missing
+        ));
+    }
+
+    defs.iter()
+        .filter(|d| d.key().is_empty())
+        .for_each(|d| report.error(format!("{} {} has an empty key", what, d.index())));
+
+    defs.iter().filter(|d| !d.key().is_empty()).for_each(|d| {
+        ["title", "desc"]
+            .iter()
+            .map(|suffix| format!("{}_{}", d.key(), suffix))
+            .filter(|entry| !i18n.contains_key(entry))
+            .for_each(|entry| {
+                let msg = format!("missing i18n entry '{}'", entry);
+                if PENDING_TEXT.contains(&what) {
+                    report.warning(msg);
+                } else {
+                    report.error(msg);
+                }
+            });
+    });
+
+    if UNSPRITED.contains(&what) {
+        return;
+    }
+    let path = format!("{}/{}/index.ron", textures, what);
+    if !Path::new(&path).exists() {
+        report.error(format!("no sprite index for {} at '{}'", what, path));
+        return;
+    }
+    match File::open(&path).map(from_reader) {
+        Ok(Ok(SpriteFolderDef { keys, .. })) => defs
+            .iter()
+            .filter(|d| !keys.iter().any(|k| k == d.key()))
+            .for_each(|d| {
+                let msg = format!("{} '{}' has no sprites", what, d.key());
+                if PENDING_SPRITES.contains(&(what, d.key())) {
+                    report.warning(msg);
+                } else {
+                    report.error(msg);
+                }
+            }),
+        Ok(Err(e)) => report.error(format!("could not parse sprite index '{}': {}", path, e)),
+        Err(e) => report.error(format!("could not open sprite index '{}': {}", path, e)),
+    }
 }
+
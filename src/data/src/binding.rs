@@ -0,0 +1,176 @@
+use super::lobby::*;
+use serde_derive::*;
+use std::collections::HashMap;
+
+/// What a button does during a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameAction {
+    Interact,
+    Special,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl GameAction {
+    pub fn all() -> &'static [GameAction] {
+        &[
+            GameAction::Interact,
+            GameAction::Special,
+            GameAction::North,
+            GameAction::South,
+            GameAction::West,
+            GameAction::East,
+        ]
+    }
+}
+
+/// Gamepad buttons, named after gilrs' so profiles do not depend on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PadButton {
+    South,
+    East,
+    North,
+    West,
+    LeftTrigger,
+    LeftTrigger2,
+    RightTrigger,
+    RightTrigger2,
+    Select,
+    Start,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PadStick {
+    Left,
+    Right,
+}
+
+/// How one player's gamepad maps to actions and movement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingProfile {
+    pub buttons: Vec<(PadButton, GameAction)>,
+    pub stick: PadStick,
+    pub dead_zone: f32,
+    pub invert_x: bool,
+    pub invert_y: bool,
+}
+
+impl BindingProfile {
+    /// The layout gamepads always had, half styles split the pad in its left and right sides.
+    pub fn for_style(style: Style) -> Self {
+        let buttons = match style {
+            Style::Full => vec![
+                (PadButton::South, GameAction::South),
+                (PadButton::East, GameAction::East),
+                (PadButton::North, GameAction::North),
+                (PadButton::West, GameAction::West),
+                (PadButton::LeftTrigger2, GameAction::Interact),
+                (PadButton::RightTrigger2, GameAction::Interact),
+                (PadButton::LeftTrigger, GameAction::Special),
+                (PadButton::RightTrigger, GameAction::Special),
+            ],
+            Style::HalfLeft => vec![
+                (PadButton::DPadDown, GameAction::South),
+                (PadButton::DPadRight, GameAction::East),
+                (PadButton::DPadUp, GameAction::North),
+                (PadButton::DPadLeft, GameAction::West),
+                (PadButton::LeftTrigger2, GameAction::Interact),
+                (PadButton::LeftTrigger, GameAction::Special),
+            ],
+            Style::HalfRight => vec![
+                (PadButton::South, GameAction::South),
+                (PadButton::East, GameAction::East),
+                (PadButton::North, GameAction::North),
+                (PadButton::West, GameAction::West),
+                (PadButton::RightTrigger2, GameAction::Interact),
+                (PadButton::RightTrigger, GameAction::Special),
+            ],
+        };
+        BindingProfile {
+            buttons,
+            stick: match style {
+                Style::Full | Style::HalfLeft => PadStick::Left,
+                Style::HalfRight => PadStick::Right,
+            },
+            dead_zone: 0.2,
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+
+    pub fn actions(&self, button: PadButton) -> impl Iterator<Item = GameAction> + '_ {
+        self.buttons
+            .iter()
+            .filter(move |(b, _)| *b == button)
+            .map(|(_, a)| *a)
+    }
+
+    pub fn buttons_for(&self, action: GameAction) -> Vec<PadButton> {
+        self.buttons
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(b, _)| *b)
+            .collect()
+    }
+
+    /// Makes `button` the only button doing `action` and `action` the only thing it does.
+    pub fn bind(&mut self, action: GameAction, button: PadButton) {
+        self.buttons.retain(|(b, a)| *a != action && *b != button);
+        self.buttons.push((button, action));
+    }
+
+    /// Applies the dead zone and inversion to a raw stick position.
+    pub fn stick_value(&self, x: f32, y: f32) -> (f32, f32) {
+        if (x * x + y * y).sqrt() < self.dead_zone {
+            return (0.0, 0.0);
+        }
+        (
+            if self.invert_x { -x } else { x },
+            if self.invert_y { -y } else { y },
+        )
+    }
+}
+
+/// User controls, profiles are kept per player palette key and gamepad style so a profile made
+/// for a whole pad never takes the buttons of a partner sharing it. A player without one uses
+/// the layout of their gamepad style.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlsConfig {
+    pub profiles: HashMap<String, BindingProfile>,
+}
+
+impl ControlsConfig {
+    fn slot(key: &str, style: Style) -> String {
+        format!("{}:{:?}", key, style)
+    }
+
+    pub fn profile(&self, key: &str, style: Style) -> BindingProfile {
+        self.profiles
+            .get(&ControlsConfig::slot(key, style))
+            .cloned()
+            .unwrap_or_else(|| BindingProfile::for_style(style))
+    }
+
+    pub fn has_profile(&self, key: &str, style: Style) -> bool {
+        self.profiles
+            .contains_key(&ControlsConfig::slot(key, style))
+    }
+
+    pub fn set_profile(&mut self, key: &str, style: Style, profile: BindingProfile) {
+        self.profiles
+            .insert(ControlsConfig::slot(key, style), profile);
+    }
+
+    /// Goes back to the layout of `style`.
+    pub fn reset_profile(&mut self, key: &str, style: Style) {
+        self.profiles.remove(&ControlsConfig::slot(key, style));
+    }
+}
@@ -16,6 +16,31 @@ pub const MAP_HEIGHT: f32 = 272.0;
 pub const MAP_OFFSET_X: f32 = 3.5 * BASE;
 pub const MAP_OFFSET_Y: f32 = 3.0 * BASE;
 
+// Size of each team's kitchen, in tiles.
+pub const KITCHEN_WIDTH: usize = 10;
+pub const KITCHEN_HEIGHT: usize = 11;
+pub const MIN_MAP_SPAWNS: usize = 4;
+
+pub const DEFAULT_EFFECT_DURATION: f32 = 10.0;
+
+// Power meter filled per ingredient of a delivered order, the meter goes from 0 to 1.
+pub const POWER_METER_DELIVERY_GAIN: f32 = 0.05;
+
+// Gameplay runs at a fixed rate regardless of how fast frames are rendered.
+pub const TICK_DELTA: f32 = 1.0 / 60.0;
+
+// Bumped whenever a change to the replay format or to `MatchRng` would make older replays play
+// out differently. Replays without a version were recorded with the `StdRng` generator.
+pub const REPLAY_VERSION: u32 = 1;
+// Slow frames are not caught up past this, to avoid spiraling when the game stalls.
+pub const MAX_TICKS_PER_FRAME: usize = 8;
+
+// Seconds the finished match stays frozen on screen before the results are shown.
+pub const MATCH_END_FREEZE: f32 = 2.0;
+
+// Seconds between checks for changed asset files when running with `--dev`.
+pub const HOT_RELOAD_INTERVAL: f32 = 0.5;
+
 //pub const ENEMY_RADIUS: f32 = 0.8125;
 //pub const PLAYER_RADIUS: f32 = 0.4375;
 //pub const BULLET_RADIUS: f32 = 0.0625;
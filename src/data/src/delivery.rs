@@ -0,0 +1,54 @@
+use serde_derive::*;
+
+/// How a delivered order that matches none of the team's orders is handled, set per generation
+/// profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliveryRules {
+    /// Points taken from the team for every rejected delivery.
+    pub penalty: isize,
+    /// Hands a rejected order back to the player instead of throwing it away.
+    pub return_order: bool,
+    /// Share of its worth an order close to a requested one gets, 0 turns partial credit off.
+    pub partial_credit: f32,
+    /// Least similarity, from 0 to 1, for a delivery to count as close to a requested order.
+    pub partial_threshold: f32,
+}
+
+impl Default for DeliveryRules {
+    fn default() -> Self {
+        DeliveryRules {
+            penalty: 0,
+            return_order: false,
+            partial_credit: 0.0,
+            partial_threshold: 0.75,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeliveryResult {
+    Accepted {
+        score: isize,
+    },
+    /// Fulfilled the closest requested order for part of its worth.
+    Partial {
+        score: isize,
+        similarity: f32,
+    },
+    /// Matched nothing, `penalty` is what the team actually lost.
+    Rejected {
+        penalty: isize,
+        returned: bool,
+    },
+}
+
+impl DeliveryResult {
+    /// Points the delivery added to the team's score, negative for penalties.
+    pub fn score(&self) -> isize {
+        match self {
+            DeliveryResult::Accepted { score } | DeliveryResult::Partial { score, .. } => *score,
+            DeliveryResult::Rejected { penalty, .. } => -penalty,
+        }
+    }
+}
@@ -1,20 +1,52 @@
 use super::common::*;
+use crate::Definitions;
+use rand::Rng;
 use serde_derive::*;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FloatValue {
     Fixed(f32),
     Random(f32, f32),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl FloatValue {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        match *self {
+            FloatValue::Fixed(v) => v,
+            FloatValue::Random(a, b) if a < b => rng.gen_range(a, b),
+            FloatValue::Random(a, _) => a,
+        }
+    }
+
+    /// Used where the value must not change between evaluations, e.g. when showing scores.
+    pub fn mean(&self) -> f32 {
+        match *self {
+            FloatValue::Fixed(v) => v,
+            FloatValue::Random(a, b) => (a + b) / 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DurationValue {
     Indeterminate,
     Fixed(f32),
     Random(f32, f32),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl DurationValue {
+    /// `None` means the effect lasts until the end of the match.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<f32> {
+        match *self {
+            DurationValue::Indeterminate => None,
+            DurationValue::Fixed(v) => Some(v),
+            DurationValue::Random(a, b) if a < b => Some(rng.gen_range(a, b)),
+            DurationValue::Random(a, _) => Some(a),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TeamMember {
     Captain,
     ScooperOne,
@@ -22,14 +54,14 @@ pub enum TeamMember {
     Server,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MemberCount {
     One,
     Two,
     Three,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FilterQuantity {
     WholeTeam,
     One(TeamMember),
@@ -38,40 +70,94 @@ pub enum FilterQuantity {
     Random(MemberCount),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TeamSide {
     Partner,
     Adversary,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EffectFilter {
     Carrier,
     Team(TeamSide, FilterQuantity),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EffectCondition {
     Alone,
     CombinedWithFlavor(Vec<FlavorIndex>),
     CombinedWithClass(Vec<FlavorClass>),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl EffectCondition {
+    /// Checks the condition for `subject` against every other scoop in `flavors`.
+    /// Each combination list is satisfied by any one of its entries.
+    pub fn is_met(
+        &self,
+        subject: &FlavorIndex,
+        flavors: &[FlavorIndex],
+        defs: &Definitions,
+    ) -> bool {
+        let mut others = flavors.to_vec();
+        if let Some(i) = others.iter().position(|f| f == subject) {
+            others.remove(i);
+        }
+        match self {
+            EffectCondition::Alone => others.iter().all(|f| f == subject),
+            EffectCondition::CombinedWithFlavor(list) => others.iter().any(|f| list.contains(f)),
+            EffectCondition::CombinedWithClass(list) => others.iter().any(|f| {
+                defs.get_flavor(f)
+                    .map(|d| list.contains(&d.class))
+                    .unwrap_or(false)
+            }),
+        }
+    }
+
+    /// Checks the condition against the whole order, for effects that do not come from a scoop.
+    pub fn is_met_by_order(&self, flavors: &[FlavorIndex], defs: &Definitions) -> bool {
+        match self {
+            EffectCondition::Alone => flavors.windows(2).all(|w| w[0] == w[1]),
+            EffectCondition::CombinedWithFlavor(list) => flavors.iter().any(|f| list.contains(f)),
+            EffectCondition::CombinedWithClass(list) => flavors.iter().any(|f| {
+                defs.get_flavor(f)
+                    .map(|d| list.contains(&d.class))
+                    .unwrap_or(false)
+            }),
+        }
+    }
+
+    pub fn all_met(
+        conditions: &[EffectCondition],
+        subject: &FlavorIndex,
+        flavors: &[FlavorIndex],
+        defs: &Definitions,
+    ) -> bool {
+        conditions.iter().all(|c| c.is_met(subject, flavors, defs))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum EffectSource {
+    Flavor(FlavorIndex),
+    Preparation(PreparationIndex),
+    Topping(ToppingIndex),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Color {
     Red,
     Yellow,
     Green,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ScreenEffect {
     CreamClouds,
     Dizzy,
     Pulse(Color),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EffectDefinition {
     OrderTotalScore(FloatValue),
     Speed(EffectFilter, FloatValue, DurationValue),
@@ -0,0 +1,66 @@
+use super::{common::*, delivery::*, order::*};
+use amethyst::ecs::prelude::*;
+
+#[derive(Debug, Clone)]
+pub enum OrderEvent {
+    Scoop {
+        player: Entity,
+        team: usize,
+        flavor: FlavorIndex,
+    },
+    Pickup {
+        player: Entity,
+        team: usize,
+    },
+    Topping {
+        player: Entity,
+        team: usize,
+        topping: ToppingIndex,
+    },
+    Delivery {
+        player: Entity,
+        team: usize,
+        order: Order,
+        score: isize,
+    },
+    /// The delivered order was close enough to a requested one to earn partial credit, it does
+    /// not trigger the order's effects nor fill the power meter.
+    PartialDelivery {
+        player: Entity,
+        team: usize,
+        order: Order,
+        score: isize,
+    },
+    /// The delivered order matched none of the team's orders.
+    WrongDelivery {
+        player: Entity,
+        team: usize,
+        order: Order,
+    },
+    /// The order melted in the player's hands.
+    Melted {
+        player: Entity,
+        team: usize,
+    },
+    /// A requested order ran out of time, `penalty` is what the team actually lost.
+    Expired {
+        team: usize,
+        penalty: isize,
+    },
+}
+
+/// Sent for every order brought to a delivery table, whether it was accepted or not.
+#[derive(Debug, Clone)]
+pub struct DeliveryEvent {
+    pub player: Entity,
+    pub team: usize,
+    pub result: DeliveryResult,
+}
+
+/// Sent when a player spends the team's power meter on its special.
+#[derive(Debug, Clone)]
+pub struct HissatsuEvent {
+    pub player: Entity,
+    pub team: usize,
+    pub hissatsu: HissatsuIndex,
+}
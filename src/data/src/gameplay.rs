@@ -1,6 +1,19 @@
-use super::{common::*, order::*};
+use super::{common::*, def::Definitions, delivery::*, effect::*, generation::*, order::*};
 use amethyst::ecs::prelude::*;
 
+#[derive(Debug, Clone)]
+pub enum TeamModifierKind {
+    MeltSpeed(f32),
+    BlockSpecial,
+    Screen(ScreenEffect),
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamModifier {
+    pub kind: TeamModifierKind,
+    pub timer: Option<f32>,
+}
+
 #[derive(Debug)]
 pub struct Team {
     pub captain: Entity,
@@ -10,18 +23,233 @@ pub struct Team {
     pub flavors: Vec<FlavorIndex>,
     pub preparations: Vec<PreparationIndex>,
     pub toppings: Vec<ToppingIndex>,
+    pub hissatsu: Option<HissatsuIndex>,
     pub power_meter: f32,
     pub score: isize,
     pub orders: Vec<OrderDefinition>,
+    pub modifiers: Vec<TeamModifier>,
     pub parent: Entity,
 }
 
+impl Team {
+    /// Fills the roles from the players that joined, `members` must not be empty. A team without
+    /// a captain is led by its first player and one without a server has the captain serve.
+    pub fn new(members: &[(TeamMember, Entity)], parent: Entity) -> Self {
+        let member = |m: TeamMember| members.iter().find(|(x, _)| *x == m).map(|(_, e)| *e);
+        let captain = member(TeamMember::Captain).unwrap_or(members[0].1);
+        Team {
+            captain,
+            server: member(TeamMember::Server).unwrap_or(captain),
+            scooper_one: member(TeamMember::ScooperOne),
+            scooper_two: member(TeamMember::ScooperTwo),
+            flavors: vec![],
+            preparations: vec![],
+            toppings: vec![],
+            hissatsu: None,
+            power_meter: 0.0,
+            score: 0,
+            orders: vec![],
+            modifiers: vec![],
+            parent,
+        }
+    }
+
+    pub fn member(&self, member: &TeamMember) -> Option<Entity> {
+        match member {
+            TeamMember::Captain => Some(self.captain),
+            TeamMember::Server => Some(self.server),
+            TeamMember::ScooperOne => self.scooper_one,
+            TeamMember::ScooperTwo => self.scooper_two,
+        }
+    }
+
+    pub fn members(&self) -> Vec<Entity> {
+        let mut members = vec![self.captain, self.server];
+        members.extend(self.scooper_one);
+        members.extend(self.scooper_two);
+        members.dedup();
+        members
+    }
+
+    pub fn melt_speed(&self) -> f32 {
+        self.modifiers.iter().fold(1.0, |acc, m| match m.kind {
+            TeamModifierKind::MeltSpeed(v) => acc * v,
+            _ => acc,
+        })
+    }
+
+    pub fn special_blocked(&self) -> bool {
+        self.modifiers.iter().any(|m| match m.kind {
+            TeamModifierKind::BlockSpecial => true,
+            _ => false,
+        })
+    }
+
+    pub fn can_use_special(&self, cost: f32) -> bool {
+        self.hissatsu.is_some() && self.power_meter >= cost && !self.special_blocked()
+    }
+
+    /// Scores `order` against every outstanding order following `rules`, removing the order it
+    /// fulfils. Partial credit is a share of what the requested order would have been worth with
+    /// the freshness of `order`. The score never goes below zero.
+    pub fn deliver(
+        &mut self,
+        order: &Order,
+        rules: &DeliveryRules,
+        defs: &Definitions,
+    ) -> DeliveryResult {
+        if let Some(i) = self.orders.iter().position(|o| o.matches(order)) {
+            self.orders.remove(i);
+            let score = order.calculate_worth(defs).total();
+            self.score += score;
+            return DeliveryResult::Accepted { score };
+        }
+
+        if rules.partial_credit > 0.0 {
+            let closest = self
+                .orders
+                .iter()
+                .map(|o| o.similarity(order))
+                .enumerate()
+                .filter(|(_, s)| *s >= rules.partial_threshold)
+                .fold(None, |best: Option<(usize, f32)>, (i, s)| match best {
+                    Some((_, b)) if b >= s => best,
+                    _ => Some((i, s)),
+                });
+            if let Some((i, similarity)) = closest {
+                let requested = self.orders.remove(i);
+                let mut fulfilled = order.clone();
+                fulfilled.flavors = requested.recipe.flavors().to_vec();
+                fulfilled.toppings = requested.recipe.toppings().to_vec();
+                let score = (fulfilled.calculate_worth(defs).value() * rules.partial_credit).round()
+                    as isize;
+                self.score += score;
+                return DeliveryResult::Partial { score, similarity };
+            }
+        }
+
+        let penalty = rules.penalty.min(self.score).max(0);
+        self.score -= penalty;
+        DeliveryResult::Rejected {
+            penalty,
+            returned: rules.return_order,
+        }
+    }
+
+    /// Time left of the order shown in HUD slot `slot`, see `OrderDefinition::time_left`.
+    pub fn order_time_left(&self, slot: usize) -> Option<f32> {
+        self.orders.get(slot).map(|o| o.time_left())
+    }
+
+    pub fn update_modifiers(&mut self, ds: f32) {
+        for m in self.modifiers.iter_mut() {
+            if let Some(ref mut t) = m.timer {
+                *t -= ds;
+            }
+        }
+        self.modifiers
+            .retain(|m| m.timer.map(|t| t > 0.0).unwrap_or(true));
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Match {
     pub teams: Vec<Team>,
     pub flavors: Vec<FlavorIndex>,
     pub preparations: Vec<PreparationIndex>,
     pub toppings: Vec<ToppingIndex>,
+    pub generation: GenerationProfile,
     pub order_gen_timer: f32,
     pub timer: f32,
+    pub elapsed: f32,
+}
+
+impl Match {
+    pub fn is_over(&self) -> bool {
+        self.timer <= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn defs() -> Definitions {
+        Definitions::new(
+            vec![flavor(0, 20.0), flavor(1, 20.0), flavor(2, 40.0)],
+            vec![preparation(0, 3, 0)],
+            vec![],
+        )
+    }
+
+    fn team(orders: &[&[usize]]) -> Team {
+        let mut world = World::new();
+        let player = world.create_entity().build();
+        let parent = world.create_entity().build();
+        let mut team = Team::new(&[(TeamMember::Captain, player)], parent);
+        team.orders = orders
+            .iter()
+            .map(|f| OrderDefinition::new(recipe(f, &[]), PreparationIndex(0), 60.0))
+            .collect();
+        team.score = 10;
+        team
+    }
+
+    #[test]
+    fn deliver_accepts_a_matching_order() {
+        let mut team = team(&[&[0, 2], &[0, 1]]);
+        let result = team.deliver(&order(0, &[1, 0], &[]), &DeliveryRules::default(), &defs());
+
+        // 40 points with the freshness bonus of an order just made.
+        assert_eq!(result, DeliveryResult::Accepted { score: 46 });
+        assert_eq!(team.score, 56);
+        assert_eq!(team.orders.len(), 1);
+        assert_eq!(team.orders[0].recipe, recipe(&[0, 2], &[]));
+    }
+
+    #[test]
+    fn deliver_credits_part_of_the_closest_requested_order() {
+        let rules = DeliveryRules {
+            partial_credit: 0.5,
+            partial_threshold: 0.5,
+            ..Default::default()
+        };
+        let mut team = team(&[&[0, 1, 2]]);
+        let result = team.deliver(&order(0, &[0, 1], &[]), &rules, &defs());
+
+        // Half of the 80 points the requested order is worth, with the freshness bonus.
+        match result {
+            DeliveryResult::Partial { score, similarity } => {
+                assert_eq!(score, 46);
+                assert!((similarity - 2.0 / 3.0).abs() < 1e-6);
+            }
+            other => panic!("expected partial credit, got {:?}", other),
+        }
+        assert_eq!(team.score, 56);
+        assert!(team.orders.is_empty());
+    }
+
+    #[test]
+    fn deliver_rejects_an_order_below_the_threshold() {
+        let rules = DeliveryRules {
+            penalty: 25,
+            return_order: true,
+            partial_credit: 0.5,
+            partial_threshold: 0.75,
+        };
+        let mut team = team(&[&[0, 1, 2]]);
+        let result = team.deliver(&order(0, &[0, 1], &[]), &rules, &defs());
+
+        // The penalty never takes the score below zero.
+        assert_eq!(
+            result,
+            DeliveryResult::Rejected {
+                penalty: 10,
+                returned: true,
+            }
+        );
+        assert_eq!(team.score, 0);
+        assert_eq!(team.orders.len(), 1);
+    }
 }
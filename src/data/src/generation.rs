@@ -0,0 +1,119 @@
+use super::{common::*, delivery::*, order::*};
+use serde_derive::*;
+
+/// Piecewise linear curve over `(x, y)` points sorted by `x`, clamped at both ends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Curve(pub Vec<(f32, f32)>);
+
+impl Curve {
+    pub fn sample(&self, x: f32) -> f32 {
+        let points = &self.0;
+        match (points.first(), points.last()) {
+            (Some(first), _) if x <= first.0 => first.1,
+            (_, Some(last)) if x >= last.0 => last.1,
+            (None, _) | (_, None) => 0.0,
+            _ => points
+                .windows(2)
+                .find(|w| x >= w[0].0 && x <= w[1].0)
+                .map(|w| {
+                    let t = (x - w[0].0) / (w[1].0 - w[0].0);
+                    w[0].1 + (w[1].1 - w[0].1) * t
+                })
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShapeWeight {
+    pub shape: RecipeShape,
+    pub weight: f32,
+    /// Added to `weight` scaled by the current difficulty.
+    pub ramp: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationProfile {
+    pub key: String,
+    pub initial_delay: f32,
+    /// Seconds between orders, sampled by elapsed match time.
+    pub spawn_interval: Curve,
+    pub max_outstanding: usize,
+    pub shapes: Vec<ShapeWeight>,
+    /// Difficulty from 0.0 to 1.0, sampled by elapsed match time.
+    pub difficulty: Curve,
+    pub order_timer: f32,
+    /// Multiplier applied to `order_timer` at full difficulty.
+    pub order_timer_ramp: f32,
+    pub default_flavor_weight: f32,
+    pub flavor_weights: Vec<(FlavorIndex, f32)>,
+    #[serde(default)]
+    pub delivery: DeliveryRules,
+    /// Points taken from a team for every order that ran out of time.
+    #[serde(default)]
+    pub expiry_penalty: isize,
+}
+
+impl GenerationProfile {
+    pub fn interval_at(&self, elapsed: f32) -> f32 {
+        self.spawn_interval.sample(elapsed).max(0.1)
+    }
+
+    pub fn difficulty_at(&self, elapsed: f32) -> f32 {
+        self.difficulty.sample(elapsed).max(0.0).min(1.0)
+    }
+
+    pub fn order_timer_at(&self, elapsed: f32) -> f32 {
+        let difficulty = self.difficulty_at(elapsed);
+        self.order_timer * (1.0 + (self.order_timer_ramp - 1.0) * difficulty)
+    }
+
+    pub fn shape_weight(&self, shape: &RecipeShape, elapsed: f32) -> f32 {
+        let difficulty = self.difficulty_at(elapsed);
+        self.shapes
+            .iter()
+            .find(|s| s.shape == *shape)
+            .map(|s| (s.weight + s.ramp * difficulty).max(0.0))
+            .unwrap_or(0.0)
+    }
+
+    pub fn flavor_weight(&self, flavor: &FlavorIndex) -> f32 {
+        self.flavor_weights
+            .iter()
+            .find(|(f, _)| f == flavor)
+            .map(|(_, w)| *w)
+            .unwrap_or(self.default_flavor_weight)
+    }
+}
+
+impl Default for GenerationProfile {
+    fn default() -> Self {
+        let shape = |scoops, toppings| ShapeWeight {
+            shape: RecipeShape { scoops, toppings },
+            weight: 1.0,
+            ramp: 0.0,
+        };
+        GenerationProfile {
+            key: String::from("default"),
+            initial_delay: 5.0,
+            spawn_interval: Curve(vec![(0.0, 20.0)]),
+            max_outstanding: 8,
+            shapes: vec![
+                shape(1, 0),
+                shape(2, 0),
+                shape(3, 0),
+                shape(4, 0),
+                shape(1, 1),
+                shape(2, 1),
+                shape(3, 1),
+            ],
+            difficulty: Curve(vec![(0.0, 0.0)]),
+            order_timer: 45.0,
+            order_timer_ramp: 1.0,
+            default_flavor_weight: 1.0,
+            flavor_weights: Vec::new(),
+            delivery: DeliveryRules::default(),
+            expiry_penalty: 0,
+        }
+    }
+}
@@ -1,8 +1,31 @@
-use super::common::*;
+use super::{common::*, constants::*, effect::*};
 use serde_derive::*;
 
+/// A team special, bought with `cost` of the power meter.
+/// Effects that do not name a team of their own land on `target`, and `duration` is how long
+/// the effects without a duration of their own last.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HissatsuDef {
     pub index: HissatsuIndex,
     pub key: String,
+    #[serde(default = "default_cost")]
+    pub cost: f32,
+    #[serde(default)]
+    pub effect: Vec<EffectDefinition>,
+    #[serde(default = "default_duration")]
+    pub duration: DurationValue,
+    #[serde(default = "default_target")]
+    pub target: TeamSide,
+}
+
+fn default_cost() -> f32 {
+    1.0
+}
+
+fn default_duration() -> DurationValue {
+    DurationValue::Fixed(DEFAULT_EFFECT_DURATION)
+}
+
+fn default_target() -> TeamSide {
+    TeamSide::Adversary
 }
@@ -1,68 +1,374 @@
 mod animation;
+mod binding;
 mod common;
 mod constants;
+mod delivery;
 mod effect;
+mod event;
 mod flavor;
 mod gameplay;
+mod generation;
 mod hissatsu;
 mod house;
+mod lobby;
 mod map;
 mod order;
 mod player;
 mod preparation;
+mod random;
+mod replay;
+mod score;
 mod sound;
 mod sprite_folder;
+mod stats;
 mod team;
+#[cfg(test)]
+mod testing;
 mod texture;
+mod tick;
 mod topping;
 mod ui;
 
 mod def {
+    use super::common::*;
+    use std::{collections::HashMap, error::Error, fmt, hash::Hash};
+
+    /// Two entries of a data file share an index or a key, `first` and `second` are their
+    /// positions in the file.
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum DefinitionError {
+        DuplicateIndex {
+            definition: &'static str,
+            first: usize,
+            second: usize,
+        },
+        DuplicateKey {
+            definition: &'static str,
+            key: String,
+            first: usize,
+            second: usize,
+        },
+    }
+
+    impl DefinitionError {
+        pub fn definition(&self) -> &'static str {
+            match self {
+                DefinitionError::DuplicateIndex { definition, .. } => definition,
+                DefinitionError::DuplicateKey { definition, .. } => definition,
+            }
+        }
+    }
+
+    impl fmt::Display for DefinitionError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                DefinitionError::DuplicateIndex { first, second, .. } => {
+                    write!(f, "entries {} and {} share the same index", first, second)
+                }
+                DefinitionError::DuplicateKey {
+                    key, first, second, ..
+                } => write!(
+                    f,
+                    "entries {} and {} share the key '{}'",
+                    first, second, key
+                ),
+            }
+        }
+    }
+
+    impl Error for DefinitionError {}
+
+    /// Definitions of a single kind, indexed by both their index and their key.
+    #[derive(Debug)]
+    struct Table<I, D> {
+        defs: Vec<D>,
+        indices: HashMap<I, usize>,
+        keys: HashMap<String, usize>,
+    }
+
+    impl<I: Eq + Hash + Clone, D> Table<I, D> {
+        /// Adds an error to `errors` for every index or key used by more than one definition.
+        fn new(
+            definition: &'static str,
+            defs: Vec<D>,
+            index: impl Fn(&D) -> &I,
+            key: impl Fn(&D) -> &str,
+            errors: &mut Vec<DefinitionError>,
+        ) -> Self {
+            let mut indices = HashMap::new();
+            for (i, d) in defs.iter().enumerate() {
+                if let Some(first) = indices.insert(index(d).clone(), i) {
+                    errors.push(DefinitionError::DuplicateIndex {
+                        definition,
+                        first,
+                        second: i,
+                    });
+                }
+            }
+
+            Table {
+                indices,
+                ..Table::keyed(definition, defs, key, errors)
+            }
+        }
+
+        /// Like `new`, for definitions that are only looked up by key.
+        fn keyed(
+            definition: &'static str,
+            defs: Vec<D>,
+            key: impl Fn(&D) -> &str,
+            errors: &mut Vec<DefinitionError>,
+        ) -> Self {
+            let mut keys = HashMap::new();
+            for (i, d) in defs.iter().enumerate() {
+                if let Some(first) = keys.insert(key(d).to_owned(), i) {
+                    errors.push(DefinitionError::DuplicateKey {
+                        definition,
+                        key: key(d).to_owned(),
+                        first,
+                        second: i,
+                    });
+                }
+            }
+
+            Table {
+                defs,
+                indices: HashMap::new(),
+                keys,
+            }
+        }
+
+        fn get(&self, index: &I) -> Option<&D> {
+            self.indices.get(index).map(|i| &self.defs[*i])
+        }
+
+        fn get_by_key(&self, key: &str) -> Option<&D> {
+            self.keys.get(key).map(|i| &self.defs[*i])
+        }
+    }
+
+    impl<I: Eq + Hash, D> Default for Table<I, D> {
+        fn default() -> Self {
+            Table {
+                defs: Vec::new(),
+                indices: HashMap::new(),
+                keys: HashMap::new(),
+            }
+        }
+    }
+
+    /// Duplicates found while building the tables are kept until `check` so that every table can
+    /// be built and all of them reported at once.
     #[derive(Debug, Default)]
     pub struct Definitions {
-        flavors: Vec<FlavorDef>,
-        preparations: Vec<PreparationDef>,
-        toppings: Vec<ToppingDef>,
-        hissatsu: Vec<HissatsuDef>,
-        houses: Vec<HouseDef>,
-        teams: Vec<TeamDef>,
-        players: Vec<PlayerDef>,
+        flavors: Table<FlavorIndex, FlavorDef>,
+        preparations: Table<PreparationIndex, PreparationDef>,
+        toppings: Table<ToppingIndex, ToppingDef>,
+        hissatsu: Table<HissatsuIndex, HissatsuDef>,
+        houses: Table<HouseIndex, HouseDef>,
+        teams: Table<TeamIndex, TeamDef>,
+        players: Table<PlayerIndex, PlayerDef>,
+        generation: Table<String, GenerationProfile>,
+        errors: Vec<DefinitionError>,
     }
 
     impl Definitions {
         pub fn new(f: Vec<FlavorDef>, p: Vec<PreparationDef>, t: Vec<ToppingDef>) -> Self {
+            let mut errors = Vec::new();
             Definitions {
-                flavors: f,
-                preparations: p,
-                toppings: t,
-                hissatsu: Vec::new(),
-                houses: Vec::new(),
-                teams: Vec::new(),
-                players: Vec::new(),
+                flavors: Table::new(
+                    "flavors definition",
+                    f,
+                    |x| &x.index,
+                    |x| &x.key,
+                    &mut errors,
+                ),
+                preparations: Table::new(
+                    "preparations definition",
+                    p,
+                    |x| &x.index,
+                    |x| &x.key,
+                    &mut errors,
+                ),
+                toppings: Table::new(
+                    "toppings definition",
+                    t,
+                    |x| &x.index,
+                    |x| &x.key,
+                    &mut errors,
+                ),
+                errors,
+                ..Default::default()
+            }
+        }
+
+        pub fn with_hissatsu(mut self, v: Vec<HissatsuDef>) -> Self {
+            self.hissatsu = Table::new(
+                "hissatsu definition",
+                v,
+                |x| &x.index,
+                |x| &x.key,
+                &mut self.errors,
+            );
+            self
+        }
+
+        pub fn with_houses(mut self, v: Vec<HouseDef>) -> Self {
+            self.houses = Table::new(
+                "houses definition",
+                v,
+                |x| &x.index,
+                |x| &x.key,
+                &mut self.errors,
+            );
+            self
+        }
+
+        pub fn with_teams(mut self, v: Vec<TeamDef>) -> Self {
+            self.teams = Table::new(
+                "teams definition",
+                v,
+                |x| &x.index,
+                |x| &x.key,
+                &mut self.errors,
+            );
+            self
+        }
+
+        pub fn with_players(mut self, v: Vec<PlayerDef>) -> Self {
+            self.players = Table::new(
+                "players definition",
+                v,
+                |x| &x.index,
+                |x| &x.name,
+                &mut self.errors,
+            );
+            self
+        }
+
+        pub fn with_generation(mut self, g: Vec<GenerationProfile>) -> Self {
+            self.generation =
+                Table::keyed("generation definition", g, |x| &x.key, &mut self.errors);
+            self
+        }
+
+        /// Fails with the duplicates of every table built so far.
+        pub fn check(self) -> Result<Self, Vec<DefinitionError>> {
+            if self.errors.is_empty() {
+                Ok(self)
+            } else {
+                Err(self.errors)
             }
         }
 
         pub fn flavors(&self) -> std::slice::Iter<FlavorDef> {
-            self.flavors.iter()
+            self.flavors.defs.iter()
         }
 
         pub fn preparations(&self) -> std::slice::Iter<PreparationDef> {
-            self.preparations.iter()
+            self.preparations.defs.iter()
         }
 
         pub fn toppings(&self) -> std::slice::Iter<ToppingDef> {
-            self.toppings.iter()
+            self.toppings.defs.iter()
+        }
+
+        pub fn hissatsu(&self) -> std::slice::Iter<HissatsuDef> {
+            self.hissatsu.defs.iter()
+        }
+
+        pub fn houses(&self) -> std::slice::Iter<HouseDef> {
+            self.houses.defs.iter()
+        }
+
+        pub fn teams(&self) -> std::slice::Iter<TeamDef> {
+            self.teams.defs.iter()
+        }
+
+        pub fn players(&self) -> std::slice::Iter<PlayerDef> {
+            self.players.defs.iter()
+        }
+
+        pub fn get_flavor(&self, index: &FlavorIndex) -> Option<&FlavorDef> {
+            self.flavors.get(index)
+        }
+
+        pub fn get_flavor_by_key(&self, key: &str) -> Option<&FlavorDef> {
+            self.flavors.get_by_key(key)
+        }
+
+        pub fn get_preparation(&self, index: &PreparationIndex) -> Option<&PreparationDef> {
+            self.preparations.get(index)
+        }
+
+        pub fn get_preparation_by_key(&self, key: &str) -> Option<&PreparationDef> {
+            self.preparations.get_by_key(key)
+        }
+
+        pub fn get_topping(&self, index: &ToppingIndex) -> Option<&ToppingDef> {
+            self.toppings.get(index)
+        }
+
+        pub fn get_topping_by_key(&self, key: &str) -> Option<&ToppingDef> {
+            self.toppings.get_by_key(key)
+        }
+
+        pub fn get_hissatsu(&self, index: &HissatsuIndex) -> Option<&HissatsuDef> {
+            self.hissatsu.get(index)
+        }
+
+        pub fn get_hissatsu_by_key(&self, key: &str) -> Option<&HissatsuDef> {
+            self.hissatsu.get_by_key(key)
+        }
+
+        pub fn get_house(&self, index: &HouseIndex) -> Option<&HouseDef> {
+            self.houses.get(index)
+        }
+
+        pub fn get_house_by_key(&self, key: &str) -> Option<&HouseDef> {
+            self.houses.get_by_key(key)
+        }
+
+        pub fn get_team(&self, index: &TeamIndex) -> Option<&TeamDef> {
+            self.teams.get(index)
+        }
+
+        pub fn get_team_by_key(&self, key: &str) -> Option<&TeamDef> {
+            self.teams.get_by_key(key)
+        }
+
+        pub fn get_player(&self, index: &PlayerIndex) -> Option<&PlayerDef> {
+            self.players.get(index)
+        }
+
+        pub fn get_player_by_key(&self, name: &str) -> Option<&PlayerDef> {
+            self.players.get_by_key(name)
+        }
+
+        pub fn generation(&self) -> std::slice::Iter<GenerationProfile> {
+            self.generation.defs.iter()
+        }
+
+        /// Falls back to the `"default"` profile, then to the built-in one.
+        pub fn generation_profile(&self, key: Option<&str>) -> GenerationProfile {
+            let key = key.unwrap_or("default");
+            self.generation
+                .get_by_key(key)
+                .or_else(|| self.generation.get_by_key("default"))
+                .cloned()
+                .unwrap_or_default()
         }
     }
 
     pub use super::{
-        flavor::FlavorDef, hissatsu::HissatsuDef, house::HouseDef, player::PlayerDef,
-        preparation::PreparationDef, team::TeamDef, topping::ToppingDef,
+        flavor::FlavorDef, generation::GenerationProfile, hissatsu::HissatsuDef, house::HouseDef,
+        player::PlayerDef, preparation::PreparationDef, team::TeamDef, topping::ToppingDef,
     };
 }
 
 pub use self::{
-    animation::*, common::*, constants::*, def::*, effect::*, flavor::*, gameplay::*, hissatsu::*,
-    house::*, map::*, order::*, player::*, preparation::*, sound::*, sprite_folder::*, team::*,
-    texture::*, topping::*, ui::*,
+    animation::*, binding::*, common::*, constants::*, def::*, delivery::*, effect::*, event::*,
+    flavor::*, gameplay::*, generation::*, hissatsu::*, house::*, lobby::*, map::*, order::*,
+    player::*, preparation::*, random::*, score::*, sound::*, sprite_folder::*, stats::*, team::*,
+    texture::*, tick::*, topping::*, ui::*,
 };
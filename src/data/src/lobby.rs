@@ -0,0 +1,179 @@
+use super::effect::*;
+use serde_derive::*;
+use std::fmt;
+
+/// How much of a gamepad a player uses, half styles let two players share a pad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Style {
+    HalfLeft,
+    HalfRight,
+    Full,
+}
+
+impl Style {
+    fn overlaps(self, other: Style) -> bool {
+        match (self, other) {
+            (Style::HalfLeft, Style::HalfRight) | (Style::HalfRight, Style::HalfLeft) => false,
+            _ => true,
+        }
+    }
+}
+
+impl TeamMember {
+    pub fn key(&self) -> &'static str {
+        match self {
+            TeamMember::Captain => "captain",
+            TeamMember::Server => "server",
+            TeamMember::ScooperOne => "scooper_one",
+            TeamMember::ScooperTwo => "scooper_two",
+        }
+    }
+
+    /// Index into `MapDefinition::spawns` where this member starts the match.
+    pub fn spawn(&self) -> usize {
+        match self {
+            TeamMember::Captain => 0,
+            TeamMember::Server => 1,
+            TeamMember::ScooperOne => 2,
+            TeamMember::ScooperTwo => 3,
+        }
+    }
+}
+
+/// Where a player's inputs come from, keyboards are shared by splitting them in halves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputDevice {
+    Gamepad(usize),
+    Keyboard,
+}
+
+impl fmt::Display for InputDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputDevice::Gamepad(id) => write!(f, "gamepad {}", id),
+            InputDevice::Keyboard => write!(f, "keyboard"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LobbyPlayer {
+    pub member: TeamMember,
+    pub device: InputDevice,
+    pub style: Style,
+}
+
+/// Who plays a match, the left side is team 0 and the right side team 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LobbyConfig {
+    pub left: Vec<LobbyPlayer>,
+    pub right: Vec<LobbyPlayer>,
+}
+
+impl Default for LobbyConfig {
+    fn default() -> Self {
+        LobbyConfig {
+            left: vec![
+                LobbyPlayer {
+                    member: TeamMember::Captain,
+                    device: InputDevice::Gamepad(0),
+                    style: Style::Full,
+                },
+                LobbyPlayer {
+                    member: TeamMember::Server,
+                    device: InputDevice::Gamepad(1),
+                    style: Style::Full,
+                },
+            ],
+            right: vec![
+                LobbyPlayer {
+                    member: TeamMember::Captain,
+                    device: InputDevice::Gamepad(2),
+                    style: Style::HalfLeft,
+                },
+                LobbyPlayer {
+                    member: TeamMember::Server,
+                    device: InputDevice::Gamepad(2),
+                    style: Style::HalfRight,
+                },
+            ],
+        }
+    }
+}
+
+impl LobbyConfig {
+    /// Both sides with the suffix of their palette keys, in team index order.
+    pub fn sides(&self) -> [(&'static str, &[LobbyPlayer]); 2] {
+        [("left", &self.left), ("right", &self.right)]
+    }
+
+    pub fn palette_key(side: &str, member: &TeamMember) -> String {
+        format!("{}_{}", member.key(), side)
+    }
+
+    pub fn validate(&self) -> Vec<LobbyError> {
+        let mut errors = Vec::new();
+        for (team, (_, players)) in self.sides().iter().enumerate() {
+            if players.is_empty() || players.len() > 4 {
+                errors.push(LobbyError::PlayerCount {
+                    team,
+                    count: players.len(),
+                });
+            }
+            if !players.iter().any(|p| p.member == TeamMember::Captain) {
+                errors.push(LobbyError::NoCaptain { team });
+            }
+            for (i, player) in players.iter().enumerate() {
+                if players[..i].iter().any(|p| p.member == player.member) {
+                    errors.push(LobbyError::DuplicateMember {
+                        team,
+                        member: player.member.clone(),
+                    });
+                }
+            }
+        }
+
+        let all = self
+            .left
+            .iter()
+            .chain(self.right.iter())
+            .collect::<Vec<_>>();
+        for (i, player) in all.iter().enumerate() {
+            if all[..i]
+                .iter()
+                .any(|p| p.device == player.device && p.style.overlaps(player.style))
+            {
+                errors.push(LobbyError::DeviceTaken {
+                    device: player.device,
+                });
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LobbyError {
+    PlayerCount { team: usize, count: usize },
+    NoCaptain { team: usize },
+    DuplicateMember { team: usize, member: TeamMember },
+    DeviceTaken { device: InputDevice },
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LobbyError::PlayerCount { team, count } => {
+                write!(f, "team {} has {} players but needs 1 to 4", team, count)
+            }
+            LobbyError::NoCaptain { team } => write!(f, "team {} has no captain", team),
+            LobbyError::DuplicateMember { team, member } => {
+                write!(f, "team {} has more than one {}", team, member.key())
+            }
+            LobbyError::DeviceTaken { device } => {
+                write!(f, "{} is used by more than one player", device)
+            }
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use amethyst::core::cgmath::*;
+use crate::*;
 use serde_derive::*;
+use std::{collections::VecDeque, fmt};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TableOrientation {
@@ -93,6 +95,8 @@ impl Default for TableType {
 pub struct MapDefinition {
     pub tables: Vec<(f32, f32, TableType, TableOrientation)>,
     pub spawns: Vec<(f32, f32)>,
+    #[serde(default)]
+    pub generation: Option<String>,
 }
 
 impl MapDefinition {
@@ -134,4 +138,293 @@ impl MapDefinition {
             })
             .count()
     }
+
+    /// Tiles covered by a table, non empty tables take two tiles along their orientation.
+    pub fn table_tiles(&self, index: usize) -> Vec<(isize, isize)> {
+        let (x, y, t, o) = &self.tables[index];
+        let (w, h) = match t {
+            TableType::Empty => (1.0, 1.0),
+            _ => o.make_dim(2.0, 1.0),
+        };
+        let (x, y) = (x.floor() as isize, y.floor() as isize);
+        (0..w as isize)
+            .flat_map(|i| (0..h as isize).map(move |j| (x + i, y + j)))
+            .collect()
+    }
+
+    /// Returns every problem found, an empty list means the map can be played.
+    pub fn validate(&self) -> Vec<MapError> {
+        let mut errors = Vec::new();
+        let mut grid = vec![vec![None; KITCHEN_HEIGHT]; KITCHEN_WIDTH];
+
+        for (i, (x, y, _, _)) in self.tables.iter().enumerate() {
+            if x.fract() != 0.0 || y.fract() != 0.0 {
+                errors.push(MapError::NotAligned { table: i });
+            }
+            for (tx, ty) in self.table_tiles(i) {
+                if !in_kitchen(tx, ty) {
+                    errors.push(MapError::TableOutOfBounds { table: i });
+                    break;
+                }
+                match grid[tx as usize][ty as usize] {
+                    Some(other) => errors.push(MapError::Overlap {
+                        first: other,
+                        second: i,
+                    }),
+                    None => grid[tx as usize][ty as usize] = Some(i),
+                }
+            }
+        }
+
+        if !self.tables.iter().any(|t| {
+            if let TableType::Delivery = t.2 {
+                true
+            } else {
+                false
+            }
+        }) {
+            errors.push(MapError::NoDelivery);
+        }
+
+        if self.spawns.len() < MIN_MAP_SPAWNS {
+            errors.push(MapError::NotEnoughSpawns(self.spawns.len()));
+        }
+
+        for (s, (x, y)) in self.spawns.iter().enumerate() {
+            let (sx, sy) = (x.floor() as isize, y.floor() as isize);
+            if !in_kitchen(sx, sy) {
+                errors.push(MapError::SpawnOutOfBounds { spawn: s });
+                continue;
+            }
+            if let Some(table) = grid[sx as usize][sy as usize] {
+                errors.push(MapError::SpawnBlocked { spawn: s, table });
+                continue;
+            }
+
+            // Flood the floor from the spawn, a table can be used if any tile touches the flood.
+            let mut visited = vec![vec![false; KITCHEN_HEIGHT]; KITCHEN_WIDTH];
+            let mut reachable = vec![false; self.tables.len()];
+            let mut queue = VecDeque::new();
+            visited[sx as usize][sy as usize] = true;
+            queue.push_back((sx, sy));
+            while let Some((cx, cy)) = queue.pop_front() {
+                for (nx, ny) in vec![(cx + 1, cy), (cx - 1, cy), (cx, cy + 1), (cx, cy - 1)] {
+                    if !in_kitchen(nx, ny) || visited[nx as usize][ny as usize] {
+                        continue;
+                    }
+                    match grid[nx as usize][ny as usize] {
+                        Some(table) => reachable[table] = true,
+                        None => {
+                            visited[nx as usize][ny as usize] = true;
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+            }
+
+            for (i, (_, _, t, _)) in self.tables.iter().enumerate() {
+                if let TableType::Empty = t {
+                    continue;
+                }
+                if !reachable[i] {
+                    errors.push(MapError::Unreachable { table: i, spawn: s });
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Draws the kitchen with the top row first, tables as letters and spawns as digits.
+    pub fn render_ascii(&self) -> String {
+        let mut grid = vec![vec!['.'; KITCHEN_WIDTH]; KITCHEN_HEIGHT];
+        for (i, (_, _, t, _)) in self.tables.iter().enumerate() {
+            let c = match t {
+                TableType::Flavor(_) => 'F',
+                TableType::Preparation(_) => 'P',
+                TableType::Topping(_) => 'T',
+                TableType::Delivery => 'D',
+                TableType::Empty => '#',
+            };
+            for (x, y) in self.table_tiles(i) {
+                if in_kitchen(x, y) {
+                    let tile = &mut grid[y as usize][x as usize];
+                    *tile = if *tile == '.' { c } else { '!' };
+                }
+            }
+        }
+        for (s, (x, y)) in self.spawns.iter().enumerate() {
+            let (x, y) = (x.floor() as isize, y.floor() as isize);
+            if in_kitchen(x, y) {
+                grid[y as usize][x as usize] = std::char::from_digit((s % 10) as u32, 10).unwrap();
+            }
+        }
+
+        grid.iter()
+            .rev()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn in_kitchen(x: isize, y: isize) -> bool {
+    x >= 0 && y >= 0 && (x as usize) < KITCHEN_WIDTH && (y as usize) < KITCHEN_HEIGHT
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapError {
+    NotAligned { table: usize },
+    TableOutOfBounds { table: usize },
+    Overlap { first: usize, second: usize },
+    NoDelivery,
+    NotEnoughSpawns(usize),
+    SpawnOutOfBounds { spawn: usize },
+    SpawnBlocked { spawn: usize, table: usize },
+    Unreachable { table: usize, spawn: usize },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::NotAligned { table } => {
+                write!(f, "table {} is not aligned to the tile grid", table)
+            }
+            MapError::TableOutOfBounds { table } => write!(
+                f,
+                "table {} does not fit inside the {}x{} kitchen",
+                table, KITCHEN_WIDTH, KITCHEN_HEIGHT
+            ),
+            MapError::Overlap { first, second } => {
+                write!(f, "tables {} and {} overlap", first, second)
+            }
+            MapError::NoDelivery => write!(f, "map has no delivery table"),
+            MapError::NotEnoughSpawns(count) => write!(
+                f,
+                "map has {} spawns but needs at least {}",
+                count, MIN_MAP_SPAWNS
+            ),
+            MapError::SpawnOutOfBounds { spawn } => {
+                write!(f, "spawn {} is outside of the kitchen", spawn)
+            }
+            MapError::SpawnBlocked { spawn, table } => {
+                write!(f, "spawn {} is inside table {}", spawn, table)
+            }
+            MapError::Unreachable { table, spawn } => {
+                write!(f, "table {} cannot be reached from spawn {}", table, spawn)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A delivery table in the bottom left corner and the minimum number of spawns in the middle.
+    fn kitchen() -> MapDefinition {
+        MapDefinition {
+            tables: vec![(
+                0.0,
+                0.0,
+                TableType::Delivery,
+                TableOrientation::HorizontalBottom,
+            )],
+            spawns: (0..MIN_MAP_SPAWNS).map(|s| (2.0 + s as f32, 5.0)).collect(),
+            generation: None,
+        }
+    }
+
+    fn with_table(mut map: MapDefinition, x: f32, y: f32, t: TableType) -> MapDefinition {
+        map.tables
+            .push((x, y, t, TableOrientation::HorizontalBottom));
+        map
+    }
+
+    #[test]
+    fn playable_kitchen_has_no_errors() {
+        assert_eq!(kitchen().validate(), vec![]);
+    }
+
+    #[test]
+    fn table_between_tiles_is_not_aligned() {
+        let map = with_table(kitchen(), 3.5, 3.0, TableType::Empty);
+        assert_eq!(map.validate(), vec![MapError::NotAligned { table: 1 }]);
+    }
+
+    #[test]
+    fn table_past_the_edge_is_out_of_bounds() {
+        let map = with_table(kitchen(), 9.0, 8.0, TableType::Flavor(Priority(0)));
+        assert_eq!(
+            map.validate(),
+            vec![MapError::TableOutOfBounds { table: 1 }]
+        );
+    }
+
+    #[test]
+    fn tables_sharing_a_tile_overlap() {
+        let map = with_table(kitchen(), 1.0, 0.0, TableType::Empty);
+        assert_eq!(
+            map.validate(),
+            vec![MapError::Overlap {
+                first: 0,
+                second: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn kitchen_needs_a_delivery_table() {
+        let mut map = kitchen();
+        map.tables.clear();
+        assert_eq!(map.validate(), vec![MapError::NoDelivery]);
+    }
+
+    #[test]
+    fn kitchen_needs_enough_spawns() {
+        let mut map = kitchen();
+        map.spawns.pop();
+        assert_eq!(
+            map.validate(),
+            vec![MapError::NotEnoughSpawns(MIN_MAP_SPAWNS - 1)]
+        );
+    }
+
+    #[test]
+    fn spawn_outside_the_kitchen_is_out_of_bounds() {
+        let mut map = kitchen();
+        map.spawns.push((-1.0, 5.0));
+        assert_eq!(
+            map.validate(),
+            vec![MapError::SpawnOutOfBounds {
+                spawn: MIN_MAP_SPAWNS
+            }]
+        );
+    }
+
+    #[test]
+    fn spawn_on_a_table_is_blocked() {
+        let mut map = kitchen();
+        map.spawns.push((1.5, 0.5));
+        assert_eq!(
+            map.validate(),
+            vec![MapError::SpawnBlocked {
+                spawn: MIN_MAP_SPAWNS,
+                table: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn walled_off_table_is_unreachable() {
+        let map = with_table(kitchen(), 0.0, 1.0, TableType::Empty);
+        let map = with_table(map, 1.0, 1.0, TableType::Empty);
+        let map = with_table(map, 2.0, 0.0, TableType::Empty);
+        assert_eq!(
+            map.validate(),
+            (0..MIN_MAP_SPAWNS)
+                .map(|spawn| MapError::Unreachable { table: 0, spawn })
+                .collect::<Vec<_>>()
+        );
+    }
 }
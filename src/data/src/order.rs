@@ -1,180 +1,154 @@
 use super::common::*;
 use crate::*;
 use log::*;
+use serde_derive::*;
 
-#[derive(Debug)]
-pub enum OrderPossibility {
-    OneFlavorNoTopping(FlavorIndex),
-    TwoFlavorsNoTopping(FlavorIndex, FlavorIndex),
-    ThreeFlavorsNoTopping(FlavorIndex, FlavorIndex, FlavorIndex),
-    FourFlavorsNoTopping(FlavorIndex, FlavorIndex, FlavorIndex, FlavorIndex),
-    OneFlavorWithTopping(FlavorIndex, ToppingIndex),
-    TwoFlavorsWithTopping(FlavorIndex, FlavorIndex, ToppingIndex),
-    ThreeFlavorsWithTopping(FlavorIndex, FlavorIndex, FlavorIndex, ToppingIndex),
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecipeShape {
+    pub scoops: usize,
+    pub toppings: usize,
+}
+
+/// Multiset of flavors and toppings, kept sorted so comparison ignores insertion order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Recipe {
+    flavors: Vec<FlavorIndex>,
+    toppings: Vec<ToppingIndex>,
+}
+
+impl Recipe {
+    pub fn new(mut flavors: Vec<FlavorIndex>, mut toppings: Vec<ToppingIndex>) -> Self {
+        flavors.sort_unstable();
+        toppings.sort_unstable();
+        Recipe { flavors, toppings }
+    }
+
+    pub fn flavors(&self) -> &[FlavorIndex] {
+        &self.flavors
+    }
+
+    pub fn toppings(&self) -> &[ToppingIndex] {
+        &self.toppings
+    }
+
+    /// Ingredients both recipes have, counting repeated ones.
+    pub fn shared(&self, other: &Recipe) -> usize {
+        fn count<T: Ord>(a: &[T], b: &[T]) -> usize {
+            let (mut i, mut j, mut n) = (0, 0, 0);
+            while i < a.len() && j < b.len() {
+                if a[i] < b[j] {
+                    i += 1;
+                } else if a[i] > b[j] {
+                    j += 1;
+                } else {
+                    n += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+            n
+        }
+        count(&self.flavors, &other.flavors) + count(&self.toppings, &other.toppings)
+    }
+
+    pub fn len(&self) -> usize {
+        self.flavors.len() + self.toppings.len()
+    }
+
+    pub fn shape(&self) -> RecipeShape {
+        RecipeShape {
+            scoops: self.flavors.len(),
+            toppings: self.toppings.len(),
+        }
+    }
+
+    pub fn fits(&self, preparation: &PreparationDef) -> bool {
+        preparation.shapes().contains(&self.shape())
+    }
+
+    /// Animation keys of each ingredient, flavors first, as shown in the order slots.
+    pub fn ingredient_keys(&self, defs: &Definitions) -> Vec<String> {
+        self.flavors
+            .iter()
+            .filter_map(|f| defs.get_flavor(f).map(|x| x.key.clone()))
+            .chain(
+                self.toppings
+                    .iter()
+                    .filter_map(|t| defs.get_topping(t).map(|x| x.key.clone())),
+            )
+            .collect()
+    }
 }
 
 #[derive(Debug)]
 pub struct OrderDefinition {
-    pub possibility: OrderPossibility,
+    pub recipe: Recipe,
     pub preparation: PreparationIndex,
     pub timer: f32,
     initial_timer: f32,
 }
 
 impl OrderDefinition {
-    pub fn new(possibility: OrderPossibility, preparation: PreparationIndex, timer: f32) -> Self {
+    pub fn new(recipe: Recipe, preparation: PreparationIndex, timer: f32) -> Self {
         OrderDefinition {
-            possibility,
+            recipe,
             preparation,
             timer,
             initial_timer: timer,
         }
     }
 
+    pub fn initial_timer(&self) -> f32 {
+        self.initial_timer
+    }
+
+    /// Share of the time to fulfil the order that is left, from 1 when requested to 0.
+    pub fn time_left(&self) -> f32 {
+        if self.initial_timer <= 0.0 {
+            return 0.0;
+        }
+        (self.timer / self.initial_timer).max(0.0).min(1.0)
+    }
+
+    pub fn has_expired(&self) -> bool {
+        self.timer <= 0.0
+    }
+
+    /// Stretches (or shrinks) the time left to fulfil the order, keeping the percentage left.
+    pub fn scale_timer(&mut self, factor: f32) {
+        let factor = factor.max(0.0);
+        self.timer *= factor;
+        self.initial_timer *= factor;
+    }
+
     pub fn matches(&self, order: &Order) -> bool {
+        order.preparation == self.preparation && order.recipe() == self.recipe
+    }
+
+    /// From 0 to 1, how much of this order and `order` is the same. Orders with different
+    /// preparations are never similar.
+    pub fn similarity(&self, order: &Order) -> f32 {
         if order.preparation != self.preparation {
-            false
-        } else {
-            match &self.possibility {
-                OrderPossibility::OneFlavorNoTopping(ref f1) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(ref a), None, None, None, None) => a == f1,
-                        _ => false,
-                    }
-                }
-                OrderPossibility::TwoFlavorsNoTopping(ref f1, ref f2) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), Some(b), None, None, None) => {
-                            (a == f1 && b == f2) || (a == f2 && b == f1)
-                        }
-                        _ => false,
-                    }
-                }
-                OrderPossibility::ThreeFlavorsNoTopping(ref f1, ref f2, ref f3) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), Some(b), Some(c), None, None) => {
-                            (a == f1 && b == f2 && c == f3)
-                                || (a == f1 && c == f2 && b == f3)
-                                || (b == f1 && a == f2 && c == f3)
-                                || (b == f1 && c == f2 && a == f3)
-                                || (c == f1 && a == f2 && b == f3)
-                                || (c == f1 && b == f2 && a == f3)
-                        }
-                        _ => false,
-                    }
-                }
-                OrderPossibility::FourFlavorsNoTopping(ref f1, ref f2, ref f3, ref f4) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), Some(b), Some(c), Some(d), None) => {
-                            (a == f1 && b == f2 && c == f3 && d == f4)
-                                || (a == f1 && b == f2 && d == f3 && c == f4)
-                                || (a == f1 && c == f2 && b == f3 && d == f4)
-                                || (a == f1 && c == f2 && d == f3 && b == f4)
-                                || (a == f1 && d == f2 && b == f3 && c == f4)
-                                || (a == f1 && d == f2 && c == f3 && b == f4)
-                                || (b == f1 && a == f2 && c == f3 && d == f4)
-                                || (b == f1 && a == f2 && d == f3 && c == f4)
-                                || (b == f1 && c == f2 && a == f3 && d == f4)
-                                || (b == f1 && c == f2 && d == f3 && a == f4)
-                                || (b == f1 && d == f2 && a == f3 && c == f4)
-                                || (b == f1 && d == f2 && c == f3 && a == f4)
-                                || (c == f1 && a == f2 && b == f3 && d == f4)
-                                || (c == f1 && a == f2 && d == f3 && b == f4)
-                                || (c == f1 && b == f2 && a == f3 && d == f4)
-                                || (c == f1 && b == f2 && d == f3 && a == f4)
-                                || (c == f1 && d == f2 && a == f3 && b == f4)
-                                || (c == f1 && d == f2 && b == f3 && a == f4)
-                                || (d == f1 && a == f2 && b == f3 && c == f4)
-                                || (d == f1 && a == f2 && c == f3 && b == f4)
-                                || (d == f1 && b == f2 && a == f3 && c == f4)
-                                || (d == f1 && b == f2 && c == f3 && a == f4)
-                                || (d == f1 && c == f2 && a == f3 && b == f4)
-                                || (d == f1 && c == f2 && b == f3 && a == f4)
-                        }
-                        _ => false,
-                    }
-                }
-                OrderPossibility::OneFlavorWithTopping(ref f1, ref t1) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), None, None, None, Some(t)) => a == f1 && t == t1,
-                        _ => false,
-                    }
-                }
-                OrderPossibility::TwoFlavorsWithTopping(ref f1, ref f2, ref t1) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), Some(b), None, None, Some(t)) => {
-                            (a == f1 && b == f2 && t == t1) || (a == f2 && b == f1 && t == t1)
-                        }
-                        _ => false,
-                    }
-                }
-                OrderPossibility::ThreeFlavorsWithTopping(ref f1, ref f2, ref f3, ref t1) => {
-                    match (
-                        &order.flavor_a,
-                        &order.flavor_b,
-                        &order.flavor_c,
-                        &order.flavor_d,
-                        &order.topping,
-                    ) {
-                        (Some(a), Some(b), Some(c), None, Some(t)) => {
-                            (a == f1 && b == f2 && c == f3 && t == t1)
-                                || (a == f1 && c == f2 && b == f3 && t == t1)
-                                || (b == f1 && a == f2 && c == f3 && t == t1)
-                                || (b == f1 && c == f2 && a == f3 && t == t1)
-                                || (c == f1 && a == f2 && b == f3 && t == t1)
-                                || (c == f1 && b == f2 && a == f3 && t == t1)
-                        }
-                        _ => false,
-                    }
-                }
-            }
+            return 0.0;
         }
+        let recipe = order.recipe();
+        let total = self.recipe.len().max(recipe.len());
+        if total == 0 {
+            return 0.0;
+        }
+        self.recipe.shared(&recipe) as f32 / total as f32
     }
 }
 
+/// Amounts of scoops as spelled in the names of the topping art, `sprinkles_two_bowl` and so on.
+const SCOOP_COUNTS: [&str; 8] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight",
+];
+
 #[derive(Debug, Clone)]
 pub struct Order {
-    pub flavor_a: Option<FlavorIndex>,
-    pub flavor_b: Option<FlavorIndex>,
-    pub flavor_c: Option<FlavorIndex>,
-    pub flavor_d: Option<FlavorIndex>,
-    pub topping: Option<ToppingIndex>,
+    pub flavors: Vec<FlavorIndex>,
+    pub toppings: Vec<ToppingIndex>,
     pub preparation: PreparationIndex,
     pub completed: bool,
     pub delivery_timer: f32,
@@ -184,11 +158,8 @@ pub struct Order {
 impl Order {
     pub fn new(p: PreparationIndex, f: FlavorIndex) -> Self {
         Order {
-            flavor_a: Some(f),
-            flavor_b: None,
-            flavor_c: None,
-            flavor_d: None,
-            topping: None,
+            flavors: vec![f],
+            toppings: Vec::new(),
             preparation: p,
             completed: false,
             delivery_timer: 32.0,
@@ -196,99 +167,170 @@ impl Order {
         }
     }
 
-    pub fn calculate_worth(&self, defs: &Definitions) -> isize {
+    pub fn recipe(&self) -> Recipe {
+        Recipe::new(self.flavors.clone(), self.toppings.clone())
+    }
+
+    pub fn freshness_bonus(&self) -> f32 {
         let percent_delivery_timer = self.delivery_timer / self.delivery_initial;
-        let percent_bonus = if percent_delivery_timer >= 0.8 {
+        if percent_delivery_timer >= 0.8 {
             0.15
         } else if percent_delivery_timer >= 0.5 {
             0.1
         } else {
             0.0
+        }
+    }
+
+    pub fn calculate_worth(&self, defs: &Definitions) -> ScoreBreakdown {
+        let preparation_multiplier = match defs.get_preparation(&self.preparation) {
+            Some(preparation)
+                if preparation
+                    .score_multiplier_condition
+                    .iter()
+                    .all(|c| c.is_met_by_order(&self.flavors, defs)) =>
+            {
+                preparation.score_multiplier
+            }
+            _ => 1.0,
+        };
+
+        let multipliers = if self.is_negated(defs) {
+            Vec::new()
+        } else {
+            self.sourced_effects(defs)
+                .into_iter()
+                .filter_map(|(source, e)| match e {
+                    EffectDefinition::OrderTotalScore(v) => Some(ScoreMultiplier {
+                        source,
+                        multiplier: v.mean(),
+                    }),
+                    _ => None,
+                })
+                .collect()
         };
-        let mut value = 0.0;
-        if let Some(f) = &self.flavor_a {
-            value += defs.flavors().find(|x| x.index == *f).unwrap().base_worth;
+
+        ScoreBreakdown {
+            flavors: self
+                .flavors
+                .iter()
+                .filter_map(|f| match defs.get_flavor(f) {
+                    Some(x) => Some((f.clone(), x.base_worth)),
+                    None => {
+                        warn!("Unknown flavor {:?} in order, scoring it as nothing.", f);
+                        None
+                    }
+                })
+                .collect(),
+            toppings: self
+                .toppings
+                .iter()
+                .filter_map(|t| match defs.get_topping(t) {
+                    Some(x) => Some((t.clone(), x.worth)),
+                    None => {
+                        warn!("Unknown topping {:?} in order, scoring it as nothing.", t);
+                        None
+                    }
+                })
+                .collect(),
+            preparation_multiplier,
+            multipliers,
+            freshness_bonus: self.freshness_bonus(),
         }
-        if let Some(f) = &self.flavor_b {
-            value += defs.flavors().find(|x| x.index == *f).unwrap().base_worth;
+    }
+
+    /// Collects every effect carried by this order: flavor effects whose conditions are met,
+    /// preparation effects and topping effects. A `Negate` anywhere cancels all of them.
+    pub fn active_effects(&self, defs: &Definitions) -> Vec<EffectDefinition> {
+        if self.is_negated(defs) {
+            Vec::new()
+        } else {
+            self.sourced_effects(defs)
+                .into_iter()
+                .map(|(_, e)| e)
+                .collect()
         }
-        if let Some(f) = &self.flavor_c {
-            value += defs.flavors().find(|x| x.index == *f).unwrap().base_worth;
+    }
+
+    pub fn is_negated(&self, defs: &Definitions) -> bool {
+        self.sourced_effects(defs).iter().any(|(_, e)| match e {
+            EffectDefinition::Negate => true,
+            _ => false,
+        })
+    }
+
+    fn sourced_effects(&self, defs: &Definitions) -> Vec<(EffectSource, EffectDefinition)> {
+        let mut effects = Vec::new();
+        for (f, flavor) in self
+            .flavors
+            .iter()
+            .filter_map(|f| defs.get_flavor(f).map(|x| (f, x)))
+        {
+            if EffectCondition::all_met(&flavor.condition, f, &self.flavors, defs) {
+                effects.extend(
+                    flavor
+                        .effect
+                        .iter()
+                        .map(|e| (EffectSource::Flavor(f.clone()), e.clone())),
+                );
+            }
         }
-        if let Some(f) = &self.flavor_d {
-            value += defs.flavors().find(|x| x.index == *f).unwrap().base_worth;
+        if let Some(preparation) = defs.get_preparation(&self.preparation) {
+            effects.extend(preparation.effect.iter().map(|e| {
+                (
+                    EffectSource::Preparation(self.preparation.clone()),
+                    e.clone(),
+                )
+            }));
         }
-        if let Some(t) = &self.topping {
-            value += defs.toppings().find(|x| x.index == *t).unwrap().worth;
+        for (t, topping) in self
+            .toppings
+            .iter()
+            .filter_map(|t| defs.get_topping(t).map(|x| (t, x)))
+        {
+            effects.extend(
+                topping
+                    .effect
+                    .iter()
+                    .map(|e| (EffectSource::Topping(t.clone()), e.clone())),
+            );
         }
-        let value = value
-            * (defs
-                .preparations()
-                .find(|x| x.index == self.preparation)
-                .unwrap()
-                .score_multiplier);
-        let total = value * (1.0 + percent_bonus);
-        let total = total.round() as isize;
-        total
+        effects
+    }
+
+    /// Stretches (or shrinks) the remaining delivery time, keeping the melt percentage.
+    pub fn scale_delivery(&mut self, factor: f32) {
+        let factor = factor.max(0.0);
+        self.delivery_timer *= factor;
+        self.delivery_initial *= factor;
     }
 
     pub fn flavor_count(&self) -> usize {
-        let mut count = 0;
-        if let Some(_) = self.flavor_a {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_b {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_c {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_d {
-            count += 1;
-        }
-        count
+        self.flavors.len()
     }
 
     pub fn ingredient_count(&self) -> usize {
-        let mut count = 0;
-        if let Some(_) = self.flavor_a {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_b {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_c {
-            count += 1;
-        }
-        if let Some(_) = self.flavor_d {
-            count += 1;
-        }
-        if let Some(_) = self.topping {
-            count += 1;
-        }
-        count
+        self.flavors.len() + self.toppings.len()
+    }
+
+    pub fn can_insert_flavor(&self, defs: &Definitions) -> bool {
+        defs.get_preparation(&self.preparation)
+            .map(|p| self.flavors.len() < p.max_scoops)
+            .unwrap_or(false)
+    }
+
+    pub fn can_insert_topping(&self, defs: &Definitions) -> bool {
+        defs.get_preparation(&self.preparation)
+            .map(|p| self.toppings.len() < p.topping_capacity())
+            .unwrap_or(false)
     }
 
     pub fn insert_flavor(&mut self, f: FlavorIndex) {
-        if let None = self.flavor_a {
-            self.flavor_a = Some(f);
-        } else if let None = self.flavor_b {
-            self.flavor_b = Some(f);
-        } else if let None = self.flavor_c {
-            self.flavor_c = Some(f);
-        } else if let None = self.flavor_d {
-            self.flavor_d = Some(f);
-        } else {
-            warn!("CANNOT INSERT MORE FLAVORS, SOMETHING MUST BE WRONG!");
-        }
+        self.flavors.push(f);
     }
 
     pub fn insert_topping(&mut self, t: ToppingIndex) {
-        if let None = self.topping {
-            self.topping = Some(t);
-        } else {
-            warn!("CANNOT INSERT MORE TOPPINGS, SOMETHING MUST BE WRONG!");
-        }
+        self.toppings.push(t);
     }
 
     pub fn update_delivery(&mut self, d: f32) {
@@ -296,7 +338,7 @@ impl Order {
     }
 
     pub fn has_topping(&self) -> bool {
-        self.topping.is_some()
+        !self.toppings.is_empty()
     }
 
     pub fn has_melted(&self) -> bool {
@@ -308,107 +350,33 @@ impl Order {
     }
 
     pub fn is_empty(&self) -> bool {
-        if let Some(_) = self.flavor_a {
-            false
-        } else if let Some(_) = self.flavor_b {
-            false
-        } else if let Some(_) = self.flavor_c {
-            false
-        } else if let Some(_) = self.flavor_d {
-            false
-        } else {
-            true
-        }
-    }
-
-    /// This will panic if indexes are not present inside the global definitions resource.
-    /// This will generate a panic when searching for a string key in animations for four
-    /// flavors and a topping, since we are not producing those assets and keeping generation up
-    /// to four ingredients total.
-    ///
-    pub fn get_flavor_a_key(&self, defs: &Definitions) -> String {
-        if let Some(flavor) = &self.flavor_a {
-            let flavor = defs
-                .flavors()
-                .find(|f| f.index == *flavor)
-                .unwrap()
-                .key
-                .clone();
-            format!("{}_ball", flavor)
-        } else {
-            String::from("empty_item")
-        }
+        self.flavors.is_empty()
     }
 
-    pub fn get_flavor_b_key(&self, defs: &Definitions) -> String {
-        if let Some(flavor) = &self.flavor_b {
-            let flavor = defs
-                .flavors()
-                .find(|f| f.index == *flavor)
-                .unwrap()
-                .key
-                .clone();
-            format!("{}_ball", flavor)
+    /// Unknown indexes fall back to the empty item, same as missing ingredients.
+    pub fn get_flavor_key(&self, i: usize, defs: &Definitions) -> String {
+        if let Some(flavor) = self.flavors.get(i).and_then(|f| defs.get_flavor(f)) {
+            format!("{}_ball", flavor.key)
         } else {
             String::from("empty_item")
         }
     }
 
-    pub fn get_flavor_c_key(&self, defs: &Definitions) -> String {
-        if let Some(flavor) = &self.flavor_c {
-            let flavor = defs
-                .flavors()
-                .find(|f| f.index == *flavor)
-                .unwrap()
-                .key
-                .clone();
-            format!("{}_ball", flavor)
-        } else {
-            String::from("empty_item")
-        }
-    }
-
-    pub fn get_flavor_d_key(&self, defs: &Definitions) -> String {
-        if let Some(flavor) = &self.flavor_d {
-            let flavor = defs
-                .flavors()
-                .find(|f| f.index == *flavor)
-                .unwrap()
-                .key
-                .clone();
-            format!("{}_ball", flavor)
-        } else {
-            String::from("empty_item")
-        }
-    }
-
-    pub fn get_topping_key(&self, defs: &Definitions) -> (String, String) {
-        if let Some(topping) = &self.topping {
-            let preparation = defs
-                .preparations()
-                .find(|p| p.index == self.preparation)
-                .unwrap()
-                .key
-                .clone();
-
-            let topping = defs
-                .toppings()
-                .find(|f| f.index == *topping)
-                .unwrap()
-                .key
-                .clone();
-
-            let count = match self.flavor_count() {
-                1 => "one",
-                2 => "two",
-                3 => "three",
-                4 => "four",
-                _ => std::unreachable!(),
-            };
+    /// Keys of the `i`-th topping drawn over the scoops, the fallback key is used when there is
+    /// no art for the current amount of scoops.
+    pub fn get_topping_key(&self, i: usize, defs: &Definitions) -> (String, String) {
+        let preparation = defs.get_preparation(&self.preparation);
+        let topping = self.toppings.get(i).and_then(|t| defs.get_topping(t));
+        if let (Some(preparation), Some(topping)) = (preparation, topping) {
+            let scoops = self.flavor_count().max(1);
+            let count = SCOOP_COUNTS
+                .get(scoops - 1)
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| scoops.to_string());
 
             (
-                format!("{}_{}_{}", topping, count, preparation),
-                format!("{}_single_{}", topping, preparation),
+                format!("{}_{}_{}", topping.key, count, preparation.key),
+                format!("{}_single_{}", topping.key, preparation.key),
             )
         } else {
             (String::from("empty_item"), String::from("empty_item"))
@@ -416,80 +384,79 @@ impl Order {
     }
 
     pub fn get_preparation_key(&self, defs: &Definitions) -> String {
-        defs.preparations()
-            .find(|p| p.index == self.preparation)
-            .unwrap()
-            .key
-            .clone()
+        defs.get_preparation(&self.preparation)
+            .map(|p| p.key.clone())
+            .unwrap_or_else(|| String::from("empty_item"))
     }
 
     pub fn get_key_orig(&self, defs: &Definitions) -> String {
-        let preparation = defs
-            .preparations()
-            .find(|p| p.index == self.preparation)
-            .unwrap()
-            .key
-            .clone();
-
-        let topping = if let Some(topping) = &self.topping {
-            format!(
-                "_{}",
-                defs.toppings()
-                    .find(|t| t.index == *topping)
-                    .unwrap()
-                    .key
-                    .clone()
-            )
-        } else {
-            String::from("")
-        };
+        let preparation = self.get_preparation_key(defs);
 
-        let mut flavors = Vec::with_capacity(4);
-        if let Some(flavor) = &self.flavor_a {
-            flavors.push(
-                defs.flavors()
-                    .find(|f| f.index == *flavor)
-                    .unwrap()
-                    .key
-                    .clone(),
-            );
-        }
-        if let Some(flavor) = &self.flavor_b {
-            flavors.push(
-                defs.flavors()
-                    .find(|f| f.index == *flavor)
-                    .unwrap()
-                    .key
-                    .clone(),
-            );
-        }
-        if let Some(flavor) = &self.flavor_c {
-            flavors.push(
-                defs.flavors()
-                    .find(|f| f.index == *flavor)
-                    .unwrap()
-                    .key
-                    .clone(),
-            );
-        }
-        if let Some(flavor) = &self.flavor_d {
-            flavors.push(
-                defs.flavors()
-                    .find(|f| f.index == *flavor)
-                    .unwrap()
-                    .key
-                    .clone(),
-            );
-        }
+        let mut toppings = self
+            .toppings
+            .iter()
+            .filter_map(|t| defs.get_topping(t).map(|x| x.key.clone()))
+            .collect::<Vec<_>>();
+        toppings.sort_unstable();
+        let toppings = toppings
+            .into_iter()
+            .map(|t| format!("_{}", t))
+            .collect::<String>();
+
+        let mut flavors = self
+            .flavors
+            .iter()
+            .filter_map(|f| defs.get_flavor(f).map(|x| x.key.clone()))
+            .collect::<Vec<_>>();
 
         flavors.sort_unstable();
 
-        // Attention to the last paramater, topping is optional, so it already includes the `_`
-        // when present.
-        format!("{}_{}{}", preparation, flavors.join("_"), topping)
+        // Attention to the last paramater, toppings are optional, so they already include the
+        // `_` when present.
+        format!("{}_{}{}", preparation, flavors.join("_"), toppings)
     }
 
     pub fn get_key(&self) -> &str {
         "neapolitan"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn shared_counts_repeated_ingredients_once_per_match() {
+        let a = recipe(&[0, 0, 1], &[2]);
+        assert_eq!(a.shared(&recipe(&[1, 0, 0], &[2])), 4);
+        assert_eq!(a.shared(&recipe(&[0, 2], &[])), 1);
+        assert_eq!(a.shared(&recipe(&[0, 0, 0], &[2, 2])), 3);
+        assert_eq!(a.shared(&recipe(&[3], &[0])), 0);
+    }
+
+    #[test]
+    fn fits_only_shapes_the_preparation_takes() {
+        let cone = preparation(0, 2, 1);
+        assert!(recipe(&[0], &[]).fits(&cone));
+        assert!(recipe(&[0, 1], &[0]).fits(&cone));
+        assert!(!recipe(&[0, 1, 2], &[]).fits(&cone));
+        assert!(!recipe(&[0], &[0, 1]).fits(&cone));
+        assert!(!recipe(&[], &[]).fits(&cone));
+
+        let bowl = preparation(1, 3, 0);
+        assert!(recipe(&[0, 1, 2], &[]).fits(&bowl));
+        assert!(!recipe(&[0], &[0]).fits(&bowl));
+    }
+
+    #[test]
+    fn similarity_is_the_share_of_the_bigger_recipe() {
+        let requested = OrderDefinition::new(recipe(&[0, 1, 2], &[0]), PreparationIndex(0), 60.0);
+        assert_eq!(requested.similarity(&order(0, &[2, 1, 0], &[0])), 1.0);
+        assert_eq!(requested.similarity(&order(0, &[0, 1], &[0])), 0.75);
+        assert_eq!(requested.similarity(&order(0, &[0, 1, 2, 3], &[0])), 0.8);
+        assert_eq!(requested.similarity(&order(0, &[3], &[])), 0.0);
+        // The same ingredients in another preparation are a different order.
+        assert_eq!(requested.similarity(&order(1, &[0, 1, 2], &[0])), 0.0);
+    }
+}
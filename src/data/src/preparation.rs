@@ -1,16 +1,9 @@
-use super::{common::*, effect::*};
+use super::{common::*, effect::*, order::*};
 use serde_derive::*;
+use std::fmt;
 
 pub type Position = (f32, f32);
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PreparationFlavorOffsets {
-    pub one: [Position; 1],
-    pub two: [Position; 2],
-    pub three: [Position; 3],
-    pub four: [Position; 4],
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PreparationDef {
     pub index: PreparationIndex,
@@ -22,5 +15,117 @@ pub struct PreparationDef {
     pub effect: Vec<EffectDefinition>,
     pub score_multiplier_condition: Vec<EffectCondition>,
     pub takes_topping: bool,
-    pub offsets: PreparationFlavorOffsets,
+    #[serde(default = "default_max_toppings")]
+    pub max_toppings: usize,
+    /// Where each scoop is drawn, the `n`-th entry holds the `n + 1` positions used by orders
+    /// with `n + 1` scoops.
+    pub offsets: Vec<Vec<Position>>,
+}
+
+fn default_max_toppings() -> usize {
+    1
+}
+
+/// A preparation that cannot draw every order it takes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreparationError {
+    MissingOffsets { scoops: usize },
+    WrongOffsetCount { scoops: usize, found: usize },
+}
+
+impl fmt::Display for PreparationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreparationError::MissingOffsets { scoops } => {
+                write!(f, "has no flavor offsets for {} scoops", scoops)
+            }
+            PreparationError::WrongOffsetCount { scoops, found } => {
+                write!(f, "has {} flavor offsets for {} scoops", found, scoops)
+            }
+        }
+    }
+}
+
+impl PreparationDef {
+    /// Position of the `i`-th scoop of an order with `count` scoops.
+    pub fn flavor_offset(&self, count: usize, i: usize) -> Position {
+        count
+            .checked_sub(1)
+            .and_then(|n| self.offsets.get(n))
+            .and_then(|o| o.get(i))
+            .cloned()
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// Every amount of scoops up to `max_scoops` needs one offset per scoop.
+    pub fn validate(&self) -> Vec<PreparationError> {
+        (1..=self.max_scoops)
+            .filter_map(|scoops| match self.offsets.get(scoops - 1) {
+                None => Some(PreparationError::MissingOffsets { scoops }),
+                Some(o) if o.len() != scoops => Some(PreparationError::WrongOffsetCount {
+                    scoops,
+                    found: o.len(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn topping_capacity(&self) -> usize {
+        if self.takes_topping {
+            self.max_toppings
+        } else {
+            0
+        }
+    }
+
+    /// Every recipe shape this preparation can hold.
+    pub fn shapes(&self) -> Vec<RecipeShape> {
+        let mut shapes = Vec::new();
+        for scoops in 1..=self.max_scoops {
+            for toppings in 0..=self.topping_capacity() {
+                shapes.push(RecipeShape { scoops, toppings });
+            }
+        }
+        shapes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn validate_needs_offsets_for_every_amount_of_scoops() {
+        let mut cone = preparation(0, 3, 1);
+        assert!(cone.validate().is_empty());
+
+        // Offsets for bigger orders than the preparation takes are ignored.
+        cone.offsets.push(vec![(0.0, 0.0); 4]);
+        assert!(cone.validate().is_empty());
+
+        cone.offsets.truncate(2);
+        cone.offsets[1].pop();
+        assert_eq!(
+            cone.validate(),
+            vec![
+                PreparationError::WrongOffsetCount {
+                    scoops: 2,
+                    found: 1,
+                },
+                PreparationError::MissingOffsets { scoops: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn flavor_offset_falls_back_to_the_origin() {
+        let mut cone = preparation(0, 2, 1);
+        cone.offsets[1] = vec![(1.0, 2.0), (3.0, 4.0)];
+        assert_eq!(cone.flavor_offset(2, 1), (3.0, 4.0));
+        assert_eq!(cone.flavor_offset(2, 2), (0.0, 0.0));
+        assert_eq!(cone.flavor_offset(3, 0), (0.0, 0.0));
+        assert_eq!(cone.flavor_offset(0, 0), (0.0, 0.0));
+    }
 }
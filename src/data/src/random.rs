@@ -0,0 +1,55 @@
+use rand::{Error, RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/// Seed requested for the next match, `None` picks a random one.
+#[derive(Debug, Clone, Default)]
+pub struct MatchSeed(pub Option<u64>);
+
+/// Single source of randomness for the simulation, every random decision in a match must go
+/// through it so a match can be reproduced from its seed. It is backed by ChaCha rather than
+/// `StdRng`, whose algorithm may change between `rand` releases and break saved seeds.
+pub struct MatchRng {
+    seed: u64,
+    rng: ChaChaRng,
+}
+
+impl MatchRng {
+    pub fn new(seed: u64) -> Self {
+        MatchRng {
+            seed,
+            rng: ChaChaRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_request(requested: &MatchSeed) -> Self {
+        MatchRng::new(requested.0.unwrap_or_else(rand::random))
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for MatchRng {
+    fn default() -> Self {
+        MatchRng::new(rand::random())
+    }
+}
+
+impl RngCore for MatchRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,72 @@
+use super::{common::*, lobby::*, map::*};
+use serde_derive::*;
+
+/// Snapshot of a player's `Input` component after the controllers were read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub wants_to_move: Option<Cardinal>,
+    pub last_moved_direction: Option<Cardinal>,
+    pub wants_to_interact: bool,
+    pub wants_north: bool,
+    pub wants_south: bool,
+    pub wants_west: bool,
+    pub wants_east: bool,
+    #[serde(default)]
+    pub wants_special: bool,
+}
+
+/// Inputs are keyed by the player's palette key, which is unique in a match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub delta: f32,
+    pub inputs: Vec<(String, RecordedInput)>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    /// `REPLAY_VERSION` of the build that recorded it, replays are only played by the same one.
+    #[serde(default)]
+    pub version: u32,
+    pub seed: u64,
+    pub map: MapDefinition,
+    pub flavors: Vec<FlavorIndex>,
+    pub preparations: Vec<PreparationIndex>,
+    pub toppings: Vec<ToppingIndex>,
+    pub generation: Option<String>,
+    /// Special of each team, in team index order.
+    #[serde(default)]
+    pub hissatsu: Vec<Option<HissatsuIndex>>,
+    #[serde(default)]
+    pub lobby: LobbyConfig,
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Debug)]
+pub enum ReplayMode {
+    Off,
+    Recording { path: String, replay: Replay },
+    Playing { replay: Replay, frame: usize },
+}
+
+impl Default for ReplayMode {
+    fn default() -> Self {
+        ReplayMode::Off
+    }
+}
+
+impl ReplayMode {
+    pub fn replay(&self) -> Option<&Replay> {
+        match self {
+            ReplayMode::Off => None,
+            ReplayMode::Recording { replay, .. } => Some(replay),
+            ReplayMode::Playing { replay, .. } => Some(replay),
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        match self {
+            ReplayMode::Playing { .. } => true,
+            _ => false,
+        }
+    }
+}
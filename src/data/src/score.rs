@@ -0,0 +1,139 @@
+use super::{common::*, effect::*};
+
+#[derive(Debug, Clone)]
+pub struct ScoreMultiplier {
+    pub source: EffectSource,
+    pub multiplier: f32,
+}
+
+/// Itemised worth of a delivered order, the final score is `total()`.
+#[derive(Debug, Clone)]
+pub struct ScoreBreakdown {
+    pub flavors: Vec<(FlavorIndex, f32)>,
+    pub toppings: Vec<(ToppingIndex, f32)>,
+    pub preparation_multiplier: f32,
+    pub multipliers: Vec<ScoreMultiplier>,
+    pub freshness_bonus: f32,
+}
+
+impl ScoreBreakdown {
+    pub fn base(&self) -> f32 {
+        self.flavors.iter().map(|(_, v)| v).sum::<f32>()
+            + self.toppings.iter().map(|(_, v)| v).sum::<f32>()
+    }
+
+    pub fn combo_multiplier(&self) -> f32 {
+        self.multipliers
+            .iter()
+            .fold(1.0, |acc, m| acc * m.multiplier)
+    }
+
+    pub fn value(&self) -> f32 {
+        self.base()
+            * self.preparation_multiplier
+            * self.combo_multiplier()
+            * (1.0 + self.freshness_bonus)
+    }
+
+    pub fn total(&self) -> isize {
+        self.value().round() as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::*;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 0.001
+    }
+
+    #[test]
+    fn preparation_multiplier_scales_the_base() {
+        let mut cone = preparation(0, 2, 1);
+        cone.score_multiplier = 1.5;
+        let defs = Definitions::new(
+            vec![flavor(0, 20.0), flavor(1, 20.0)],
+            vec![cone],
+            vec![topping(0, 10.0)],
+        );
+
+        let score = order(0, &[0, 1], &[0]).calculate_worth(&defs);
+        assert!(close(score.base(), 50.0));
+        assert!(close(score.preparation_multiplier, 1.5));
+        assert!(close(score.value(), 50.0 * 1.5 * 1.15));
+        assert_eq!(score.total(), 86);
+    }
+
+    #[test]
+    fn preparation_multiplier_needs_its_condition() {
+        let mut cone = preparation(0, 2, 0);
+        cone.score_multiplier = 1.5;
+        cone.score_multiplier_condition =
+            vec![EffectCondition::CombinedWithFlavor(vec![FlavorIndex(2)])];
+        let defs = Definitions::new(
+            vec![flavor(0, 20.0), flavor(1, 20.0), flavor(2, 20.0)],
+            vec![cone],
+            vec![],
+        );
+
+        let unmet = order(0, &[0, 1], &[]).calculate_worth(&defs);
+        assert!(close(unmet.preparation_multiplier, 1.0));
+        assert_eq!(unmet.total(), 46);
+
+        let met = order(0, &[0, 2], &[]).calculate_worth(&defs);
+        assert!(close(met.preparation_multiplier, 1.5));
+        assert_eq!(met.total(), 69);
+    }
+
+    #[test]
+    fn effect_multipliers_stack() {
+        let mut doubled = flavor(0, 20.0);
+        doubled.effect = vec![EffectDefinition::OrderTotalScore(FloatValue::Fixed(2.0))];
+        let mut sprinkles = topping(0, 0.0);
+        sprinkles.effect = vec![EffectDefinition::OrderTotalScore(FloatValue::Random(
+            1.0, 2.0,
+        ))];
+        let defs = Definitions::new(vec![doubled], vec![preparation(0, 1, 1)], vec![sprinkles]);
+
+        let score = order(0, &[0], &[0]).calculate_worth(&defs);
+        assert_eq!(score.multipliers.len(), 2);
+        assert!(close(score.combo_multiplier(), 3.0));
+        assert!(close(score.value(), 20.0 * 3.0 * 1.15));
+        assert_eq!(score.total(), 69);
+    }
+
+    #[test]
+    fn freshness_bonus_fades_while_the_order_waits() {
+        let defs = Definitions::new(vec![flavor(0, 100.0)], vec![preparation(0, 1, 0)], vec![]);
+        let mut waiting = order(0, &[0], &[]);
+        assert_eq!(waiting.calculate_worth(&defs).total(), 115);
+
+        waiting.delivery_timer = 20.0;
+        assert!(close(waiting.calculate_worth(&defs).freshness_bonus, 0.1));
+        assert_eq!(waiting.calculate_worth(&defs).total(), 110);
+
+        waiting.delivery_timer = 5.0;
+        assert!(close(waiting.calculate_worth(&defs).freshness_bonus, 0.0));
+        assert_eq!(waiting.calculate_worth(&defs).total(), 100);
+    }
+
+    #[test]
+    fn negated_order_drops_effect_multipliers() {
+        let mut doubled = flavor(0, 40.0);
+        doubled.effect = vec![EffectDefinition::OrderTotalScore(FloatValue::Fixed(2.0))];
+        let mut cone = preparation(0, 1, 1);
+        cone.score_multiplier = 1.5;
+        let mut negate = topping(0, 0.0);
+        negate.effect = vec![EffectDefinition::Negate];
+        let defs = Definitions::new(vec![doubled], vec![cone], vec![negate]);
+
+        let score = order(0, &[0], &[0]).calculate_worth(&defs);
+        assert!(score.multipliers.is_empty());
+        assert!(close(score.combo_multiplier(), 1.0));
+        assert!(close(score.preparation_multiplier, 1.5));
+        assert_eq!(score.total(), 69);
+    }
+}
@@ -0,0 +1,97 @@
+use super::common::*;
+use amethyst::ecs::prelude::*;
+use serde_derive::*;
+use std::collections::HashMap;
+
+/// What a player did during a match, filled by `StatsSystem` from the order events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub scoops: usize,
+    pub orders_prepared: usize,
+    pub toppings: usize,
+    pub deliveries: usize,
+    /// Deliveries that only earned partial credit.
+    #[serde(default)]
+    pub partial_deliveries: usize,
+    pub wrong_deliveries: usize,
+    pub melted: usize,
+    /// Seconds spent in scoop, preparation and topping minigames.
+    pub interaction_time: f32,
+    pub points: isize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TeamStats {
+    /// Match time and score every time the score changed.
+    pub score: Vec<(f32, isize)>,
+    /// Requested orders that ran out of time.
+    #[serde(default)]
+    pub expired: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct MatchStats {
+    pub players: HashMap<Entity, PlayerStats>,
+    pub teams: Vec<TeamStats>,
+}
+
+impl MatchStats {
+    pub fn player(&self, entity: Entity) -> PlayerStats {
+        self.players.get(&entity).cloned().unwrap_or_default()
+    }
+
+    pub fn player_mut(&mut self, entity: Entity) -> &mut PlayerStats {
+        self.players.entry(entity).or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerResult {
+    pub team: usize,
+    pub key: String,
+    pub stats: PlayerStats,
+}
+
+/// Outcome of a finished match, as shown by the results screen.
+#[derive(Debug, Clone, Default)]
+pub struct MatchResults {
+    pub scores: Vec<isize>,
+    pub players: Vec<PlayerResult>,
+    pub teams: Vec<TeamStats>,
+}
+
+impl MatchResults {
+    /// Team with the highest score, `None` on a draw.
+    pub fn winner(&self) -> Option<usize> {
+        let best = self.scores.iter().max()?;
+        let mut winners = self.scores.iter().enumerate().filter(|(_, s)| *s == best);
+        match (winners.next(), winners.next()) {
+            (Some((i, _)), None) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Player that scored the most points, nobody if no order was delivered.
+    pub fn mvp(&self) -> Option<&PlayerResult> {
+        self.players
+            .iter()
+            .filter(|p| p.stats.deliveries + p.stats.partial_deliveries > 0)
+            .max_by_key(|p| p.stats.points)
+    }
+}
+
+/// Everything recorded about a match, saved when it ends to compare balance between playtests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchReport {
+    pub seed: u64,
+    pub generation: String,
+    pub flavors: Vec<FlavorIndex>,
+    pub preparations: Vec<PreparationIndex>,
+    pub toppings: Vec<ToppingIndex>,
+    pub hissatsu: Vec<Option<HissatsuIndex>>,
+    pub duration: f32,
+    pub scores: Vec<isize>,
+    pub winner: Option<usize>,
+    pub players: Vec<PlayerResult>,
+    pub teams: Vec<TeamStats>,
+}
@@ -0,0 +1,64 @@
+//! Small hand-built definitions for unit tests, nothing here is loaded from the assets.
+
+use crate::*;
+
+pub fn flavor(index: usize, base_worth: f32) -> FlavorDef {
+    FlavorDef {
+        index: FlavorIndex(index),
+        key: format!("flavor_{}", index),
+        class: FlavorClass::Classic,
+        base_worth,
+        effect: vec![],
+        condition: vec![],
+    }
+}
+
+pub fn preparation(index: usize, max_scoops: usize, max_toppings: usize) -> PreparationDef {
+    PreparationDef {
+        index: PreparationIndex(index),
+        key: format!("preparation_{}", index),
+        score_multiplier: 1.0,
+        melt_multiplier: None,
+        effect_area_multiplier: 1.0,
+        max_scoops,
+        effect: vec![],
+        score_multiplier_condition: vec![],
+        takes_topping: max_toppings > 0,
+        max_toppings,
+        offsets: (1..=max_scoops).map(|n| vec![(0.0, 0.0); n]).collect(),
+    }
+}
+
+pub fn topping(index: usize, worth: f32) -> ToppingDef {
+    ToppingDef {
+        index: ToppingIndex(index),
+        key: format!("topping_{}", index),
+        worth,
+        effect: vec![],
+        offsets: ToppingFlavorOffsets {
+            one: (0.0, 0.0),
+            two: (0.0, 0.0),
+            three: (0.0, 0.0),
+            four: (0.0, 0.0),
+        },
+    }
+}
+
+pub fn recipe(flavors: &[usize], toppings: &[usize]) -> Recipe {
+    Recipe::new(
+        flavors.iter().map(|f| FlavorIndex(*f)).collect(),
+        toppings.iter().map(|t| ToppingIndex(*t)).collect(),
+    )
+}
+
+/// A fresh order in `preparation` holding `flavors` and `toppings`.
+pub fn order(preparation: usize, flavors: &[usize], toppings: &[usize]) -> Order {
+    let mut order = Order::new(PreparationIndex(preparation), FlavorIndex(flavors[0]));
+    for f in &flavors[1..] {
+        order.insert_flavor(FlavorIndex(*f));
+    }
+    for t in toppings {
+        order.insert_topping(ToppingIndex(*t));
+    }
+    order
+}
@@ -0,0 +1,58 @@
+use crate::*;
+
+/// Fixed gameplay step. Simulation systems integrate `delta_seconds` from here instead of the
+/// render frame `Time`, so a match plays out the same on every machine.
+#[derive(Debug, Clone)]
+pub struct Tick {
+    delta: f32,
+    frame: u64,
+    accumulator: f32,
+}
+
+impl Default for Tick {
+    fn default() -> Self {
+        Tick::new(TICK_DELTA)
+    }
+}
+
+impl Tick {
+    pub fn new(delta: f32) -> Self {
+        Tick {
+            delta,
+            frame: 0,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta
+    }
+
+    pub fn set_delta_seconds(&mut self, delta: f32) {
+        self.delta = delta;
+    }
+
+    /// Number of ticks run since the match started.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    pub fn advance(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Adds real elapsed time and returns how many ticks are due, keeping the remainder for the
+    /// next frame.
+    pub fn accumulate(&mut self, elapsed: f32) -> usize {
+        self.accumulator += elapsed;
+        let mut ticks = 0;
+        while self.accumulator >= self.delta && ticks < MAX_TICKS_PER_FRAME {
+            self.accumulator -= self.delta;
+            ticks += 1;
+        }
+        if ticks == MAX_TICKS_PER_FRAME {
+            self.accumulator = self.accumulator.min(self.delta);
+        }
+        ticks
+    }
+}
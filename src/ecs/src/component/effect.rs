@@ -8,3 +8,47 @@ pub struct Effect {
 impl Component for Effect {
     type Storage = DenseVecStorage<Self>;
 }
+
+#[derive(Debug, Clone)]
+pub enum StatusKind {
+    Speed(f32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub timer: Option<f32>,
+    /// Carrier statuses only last while the player is holding an order.
+    pub carrier: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Statuses {
+    pub statuses: Vec<Status>,
+}
+
+impl Statuses {
+    pub fn push(&mut self, status: Status) {
+        self.statuses.push(status);
+    }
+
+    pub fn speed_multiplier(&self) -> f32 {
+        self.statuses.iter().fold(1.0, |acc, s| match s.kind {
+            StatusKind::Speed(v) => acc * v,
+        })
+    }
+
+    pub fn update(&mut self, ds: f32, carrying: bool) {
+        for s in self.statuses.iter_mut() {
+            if let Some(ref mut t) = s.timer {
+                *t -= ds;
+            }
+        }
+        self.statuses
+            .retain(|s| (!s.carrier || carrying) && s.timer.map(|t| t > 0.0).unwrap_or(true));
+    }
+}
+
+impl Component for Statuses {
+    type Storage = DenseVecStorage<Self>;
+}
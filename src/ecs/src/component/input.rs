@@ -9,6 +9,7 @@ pub struct Input {
     pub wants_south: bool,
     pub wants_west: bool,
     pub wants_east: bool,
+    pub wants_special: bool,
 }
 
 impl Input {
@@ -21,8 +22,44 @@ impl Input {
             wants_south: false,
             wants_west: false,
             wants_east: false,
+            wants_special: false,
         }
     }
+
+    pub fn set_action(&mut self, action: GameAction, value: bool) {
+        match action {
+            GameAction::Interact => self.wants_to_interact = value,
+            GameAction::Special => self.wants_special = value,
+            GameAction::North => self.wants_north = value,
+            GameAction::South => self.wants_south = value,
+            GameAction::West => self.wants_west = value,
+            GameAction::East => self.wants_east = value,
+        }
+    }
+
+    pub fn record(&self) -> RecordedInput {
+        RecordedInput {
+            wants_to_move: self.wants_to_move,
+            last_moved_direction: self.last_moved_direction,
+            wants_to_interact: self.wants_to_interact,
+            wants_north: self.wants_north,
+            wants_south: self.wants_south,
+            wants_west: self.wants_west,
+            wants_east: self.wants_east,
+            wants_special: self.wants_special,
+        }
+    }
+
+    pub fn apply(&mut self, recorded: &RecordedInput) {
+        self.wants_to_move = recorded.wants_to_move;
+        self.last_moved_direction = recorded.last_moved_direction;
+        self.wants_to_interact = recorded.wants_to_interact;
+        self.wants_north = recorded.wants_north;
+        self.wants_south = recorded.wants_south;
+        self.wants_west = recorded.wants_west;
+        self.wants_east = recorded.wants_east;
+        self.wants_special = recorded.wants_special;
+    }
 }
 
 impl Component for Input {
@@ -1,7 +1,7 @@
 use amethyst::ecs::prelude::*;
 
 pub struct Interact {
-    pub highlighted_by: Option<usize>,
+    pub highlighted_by: Option<Entity>,
     pub top: Entity,
 }
 
@@ -54,9 +54,9 @@ pub struct FlavorInteraction {
 }
 
 impl FlavorInteraction {
-    pub fn new(length: f32, flavor: FlavorIndex) -> Self {
+    pub fn new<R: Rng + ?Sized>(length: f32, flavor: FlavorIndex, rng: &mut R) -> Self {
         FlavorInteraction {
-            key: rand::random(),
+            key: rng.gen(),
             length,
             progress: 0.0,
             flavor,
@@ -146,10 +146,10 @@ pub struct PreparationInteraction {
 }
 
 impl PreparationInteraction {
-    pub fn new(table: Entity, sequence_count: usize) -> Self {
+    pub fn new<R: Rng + ?Sized>(table: Entity, sequence_count: usize, rng: &mut R) -> Self {
         PreparationInteraction {
             table,
-            sequence: (0..sequence_count).map(|_| rand::random()).collect(),
+            sequence: (0..sequence_count).map(|_| rng.gen()).collect(),
             current: 0,
             progress: 0.0,
         }
@@ -234,9 +234,9 @@ pub struct ToppingInteraction {
 }
 
 impl ToppingInteraction {
-    pub fn new(topping: ToppingIndex) -> Self {
+    pub fn new<R: Rng + ?Sized>(topping: ToppingIndex, rng: &mut R) -> Self {
         ToppingInteraction {
-            pair: rand::random(),
+            pair: rng.gen(),
             current: false,
             progress: 0.0,
             topping,
@@ -320,3 +320,10 @@ impl Component for SequenceKey {
 impl Component for AlternativeKey {
     type Storage = DenseVecStorage<Self>;
 }
+
+/// Marks interactions whose bar and key prompts were already spawned.
+pub struct InteractionVisuals;
+
+impl Component for InteractionVisuals {
+    type Storage = DenseVecStorage<Self>;
+}
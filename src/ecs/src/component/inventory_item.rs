@@ -1,5 +1,7 @@
 use amethyst::ecs::prelude::*;
 
+/// Sprite of what a player carries: 0 is the preparation or the scoop, 1 to 4 the scoops of an
+/// order, 5 its melt bar and every index after that one of its toppings.
 pub struct InventoryItem(pub usize);
 
 impl Component for InventoryItem {
@@ -3,18 +3,11 @@ use crate::component::*;
 use either::*;
 use nk_data::*;
 
-#[derive(Debug)]
-pub enum Style {
-    HalfLeft,
-    HalfRight,
-    Full,
-}
-
 pub type EitherPrepTop = Either<PreparationInteraction, ToppingInteraction>;
 pub type EitherInteractions = Either<FlavorInteraction, EitherPrepTop>;
 
 pub struct Player {
-    pub gamepad_index: usize,
+    pub device: InputDevice,
     pub gamepad_style: Style,
     pub layer: f32,
     pub inventory: Option<Either<FlavorIndex, Order>>,
@@ -26,14 +19,14 @@ pub struct Player {
 
 impl Player {
     pub fn new(
-        gamepad_index: usize,
+        device: InputDevice,
         team_index: usize,
         layer: f32,
         invert_x_axis: bool,
         palette_key: String,
     ) -> Self {
         Player {
-            gamepad_index,
+            device,
             gamepad_style: Style::Full,
             layer,
             inventory: None,
@@ -45,7 +38,7 @@ impl Player {
     }
 
     pub fn new_with_style(
-        gamepad_index: usize,
+        device: InputDevice,
         gamepad_style: Style,
         team_index: usize,
         layer: f32,
@@ -53,7 +46,7 @@ impl Player {
         palette_key: String,
     ) -> Self {
         Player {
-            gamepad_index,
+            device,
             gamepad_style,
             layer,
             inventory: None,
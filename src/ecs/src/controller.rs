@@ -5,6 +5,8 @@ use amethyst::{
 };
 use crate::component::*;
 use gilrs::*;
+use log::*;
+use nk_data::{ControlsConfig, InputDevice, PadButton};
 use std::{collections::HashMap, sync::*};
 
 pub struct Controller {
@@ -39,6 +41,66 @@ impl Controller {
     }
 }
 
+impl Controller {
+    fn set_button(&mut self, button: ev::Button, pressed: bool) {
+        let value = if pressed { 1.0 } else { 0.0 };
+        match button {
+            ev::Button::South => self.actions[0] = pressed,
+            ev::Button::East => self.actions[1] = pressed,
+            ev::Button::North => self.actions[2] = pressed,
+            ev::Button::West => self.actions[3] = pressed,
+            ev::Button::DPadUp => self.d_pad.y = value,
+            ev::Button::DPadDown => self.d_pad.y = -value,
+            ev::Button::DPadLeft => self.d_pad.x = -value,
+            ev::Button::DPadRight => self.d_pad.x = value,
+            ev::Button::LeftTrigger2 => self.triggers[0] = pressed,
+            ev::Button::RightTrigger2 => self.triggers[1] = pressed,
+            ev::Button::Select => self.select = pressed,
+            ev::Button::Start => self.start = pressed,
+            ev::Button::LeftThumb => self.thumbs[0] = pressed,
+            ev::Button::RightThumb => self.thumbs[1] = pressed,
+            ev::Button::LeftTrigger => self.shoulders[0] = pressed,
+            ev::Button::RightTrigger => self.shoulders[1] = pressed,
+            _ => {}
+        }
+    }
+}
+
+/// Name of a gilrs button in binding profiles, `None` for buttons that cannot be bound.
+pub fn pad_button(button: ev::Button) -> Option<PadButton> {
+    match button {
+        ev::Button::South => Some(PadButton::South),
+        ev::Button::East => Some(PadButton::East),
+        ev::Button::North => Some(PadButton::North),
+        ev::Button::West => Some(PadButton::West),
+        ev::Button::LeftTrigger => Some(PadButton::LeftTrigger),
+        ev::Button::LeftTrigger2 => Some(PadButton::LeftTrigger2),
+        ev::Button::RightTrigger => Some(PadButton::RightTrigger),
+        ev::Button::RightTrigger2 => Some(PadButton::RightTrigger2),
+        ev::Button::Select => Some(PadButton::Select),
+        ev::Button::Start => Some(PadButton::Start),
+        ev::Button::LeftThumb => Some(PadButton::LeftThumb),
+        ev::Button::RightThumb => Some(PadButton::RightThumb),
+        ev::Button::DPadUp => Some(PadButton::DPadUp),
+        ev::Button::DPadDown => Some(PadButton::DPadDown),
+        ev::Button::DPadLeft => Some(PadButton::DPadLeft),
+        ev::Button::DPadRight => Some(PadButton::DPadRight),
+        _ => None,
+    }
+}
+
+/// Players whose gamepad was disconnected during a match, with the id the gamepad had.
+#[derive(Debug, Default)]
+pub struct LostInputs {
+    pub players: Vec<(Entity, usize)>,
+}
+
+impl LostInputs {
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+}
+
 pub struct ControllerSystem {
     reader: Option<ReaderId<ev::Event>>,
 }
@@ -53,7 +115,10 @@ impl<'s> System<'s> for ControllerSystem {
     type SystemData = (
         Read<'s, Arc<Mutex<EventChannel<ev::Event>>>>,
         Read<'s, Arc<Mutex<HashMap<usize, Controller>>>>,
-        ReadStorage<'s, Player>,
+        Read<'s, ControlsConfig>,
+        Write<'s, LostInputs>,
+        Entities<'s>,
+        WriteStorage<'s, Player>,
         WriteStorage<'s, Input>,
     );
 
@@ -67,7 +132,10 @@ impl<'s> System<'s> for ControllerSystem {
         );
     }
 
-    fn run(&mut self, (events, controllers, players, mut inputs): Self::SystemData) {
+    fn run(
+        &mut self,
+        (events, controllers, controls, mut lost, entities, mut players, mut inputs): Self::SystemData,
+    ) {
         if let Some(ref mut reader) = &mut self.reader {
             for Event {
                 id,
@@ -77,279 +145,24 @@ impl<'s> System<'s> for ControllerSystem {
             {
                 let mut controllers = controllers.lock().unwrap();
                 match event {
-                    ev::EventType::ButtonPressed(b, _) => {
+                    ev::EventType::ButtonPressed(b, _) | ev::EventType::ButtonReleased(b, _) => {
+                        let pressed = match event {
+                            ev::EventType::ButtonPressed(..) => true,
+                            _ => false,
+                        };
                         if let Some(controller) = controllers.get_mut(id) {
-                            match b {
-                                ev::Button::South => {
-                                    controller.actions[0] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::Full | Style::HalfRight => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_south = true;
+                            controller.set_button(*b, pressed);
+                            if let Some(button) = pad_button(*b) {
+                                for (player, input) in (&players, &mut inputs).join() {
+                                    if player.device != InputDevice::Gamepad(*id) {
+                                        continue;
                                     }
-                                }
-                                ev::Button::East => {
-                                    controller.actions[1] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::Full | Style::HalfRight => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_east = true;
-                                    }
-                                }
-                                ev::Button::North => {
-                                    controller.actions[2] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::Full | Style::HalfRight => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_north = true;
-                                    }
-                                }
-                                ev::Button::West => {
-                                    controller.actions[3] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::Full | Style::HalfRight => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_west = true;
+                                    let profile =
+                                        controls.profile(&player.palette_key, player.gamepad_style);
+                                    for action in profile.actions(button) {
+                                        input.set_action(action, pressed);
                                     }
                                 }
-
-                                ev::Button::DPadUp => {
-                                    controller.d_pad.y = 1.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_north = true;
-                                    }
-                                }
-                                ev::Button::DPadDown => {
-                                    controller.d_pad.y = -1.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_south = true;
-                                    }
-                                }
-                                ev::Button::DPadLeft => {
-                                    controller.d_pad.x = -1.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_west = true;
-                                    }
-                                }
-                                ev::Button::DPadRight => {
-                                    controller.d_pad.x = 1.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_east = true;
-                                    }
-                                }
-
-                                ev::Button::LeftTrigger2 => {
-                                    controller.triggers[0] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft | Style::Full => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_to_interact = true;
-                                    }
-                                }
-                                ev::Button::RightTrigger2 => {
-                                    controller.triggers[1] = true;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfRight | Style::Full => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_to_interact = true;
-                                    }
-                                }
-
-                                ev::Button::Select => controller.select = true,
-                                ev::Button::Start => controller.start = true,
-                                ev::Button::LeftThumb => controller.thumbs[0] = true,
-                                ev::Button::RightThumb => controller.thumbs[1] = true,
-                                ev::Button::LeftTrigger => controller.shoulders[0] = true,
-                                ev::Button::RightTrigger => controller.shoulders[1] = true,
-                                _ => {}
-                            }
-                        }
-                    }
-                    ev::EventType::ButtonReleased(b, _) => {
-                        if let Some(controller) = controllers.get_mut(id) {
-                            match b {
-                                ev::Button::North => {
-                                    controller.actions[2] = false;
-                                    if let Some((input, _)) = (&mut inputs, &players)
-                                        .join()
-                                        .find(|(_, p)| p.gamepad_index == *id)
-                                    {
-                                        input.wants_north = false;
-                                    }
-                                }
-                                ev::Button::South => {
-                                    controller.actions[0] = false;
-                                    if let Some((input, _)) = (&mut inputs, &players)
-                                        .join()
-                                        .find(|(_, p)| p.gamepad_index == *id)
-                                    {
-                                        input.wants_south = false;
-                                    }
-                                }
-                                ev::Button::West => {
-                                    controller.actions[3] = false;
-                                    if let Some((input, _)) = (&mut inputs, &players)
-                                        .join()
-                                        .find(|(_, p)| p.gamepad_index == *id)
-                                    {
-                                        input.wants_west = false;
-                                    }
-                                }
-                                ev::Button::East => {
-                                    controller.actions[1] = false;
-                                    if let Some((input, _)) = (&mut inputs, &players)
-                                        .join()
-                                        .find(|(_, p)| p.gamepad_index == *id)
-                                    {
-                                        input.wants_east = false;
-                                    }
-                                }
-
-                                ev::Button::DPadUp => {
-                                    controller.d_pad.y = 0.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_north = false;
-                                    }
-                                }
-                                ev::Button::DPadDown => {
-                                    controller.d_pad.y = 0.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_south = false;
-                                    }
-                                }
-                                ev::Button::DPadLeft => {
-                                    controller.d_pad.x = 0.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_west = false;
-                                    }
-                                }
-                                ev::Button::DPadRight => {
-                                    controller.d_pad.x = 0.0;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft => p.gamepad_index == *id,
-                                                Style::Full | Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_east = false;
-                                    }
-                                }
-
-                                ev::Button::LeftTrigger2 => {
-                                    controller.triggers[0] = false;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfLeft | Style::Full => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfRight => false,
-                                            }
-                                        }) {
-                                        input.wants_to_interact = false;
-                                    }
-                                }
-                                ev::Button::RightTrigger2 => {
-                                    controller.triggers[1] = false;
-                                    if let Some((input, _)) =
-                                        (&mut inputs, &players).join().find(|(_, p)| {
-                                            match p.gamepad_style {
-                                                Style::HalfRight | Style::Full => {
-                                                    p.gamepad_index == *id
-                                                }
-                                                Style::HalfLeft => false,
-                                            }
-                                        }) {
-                                        input.wants_to_interact = false;
-                                    }
-                                }
-
-                                ev::Button::Select => controller.select = false,
-                                ev::Button::Start => controller.start = false,
-                                ev::Button::LeftThumb => controller.thumbs[0] = false,
-                                ev::Button::RightThumb => controller.thumbs[1] = false,
-                                ev::Button::LeftTrigger => controller.shoulders[0] = false,
-                                ev::Button::RightTrigger => controller.shoulders[1] = false,
-                                _ => {}
                             }
                         }
                     }
@@ -384,10 +197,40 @@ impl<'s> System<'s> for ControllerSystem {
                     ev::EventType::Connected => {
                         let entry = controllers.entry(*id).or_insert(Controller::new(*id));
                         entry.active = true;
+                        info!("gamepad {} connected", id);
+
+                        // The same pad coming back keeps its players, a new one takes over the
+                        // players of the first pad that was lost.
+                        let device = InputDevice::Gamepad(*id);
+                        let old = if lost.players.iter().any(|(_, old)| old == id) {
+                            Some(*id)
+                        } else if (&players).join().any(|p| p.device == device) {
+                            None
+                        } else {
+                            lost.players.first().map(|(_, old)| *old)
+                        };
+                        if let Some(old) = old {
+                            for (entity, _) in lost.players.iter().filter(|(_, o)| *o == old) {
+                                if let Some(player) = players.get_mut(*entity) {
+                                    info!("{} now plays with gamepad {}", player.palette_key, id);
+                                    player.device = device;
+                                }
+                            }
+                            lost.players.retain(|(_, o)| *o != old);
+                        }
                     }
                     ev::EventType::Disconnected => {
                         let entry = controllers.entry(*id).or_insert(Controller::new(*id));
                         entry.active = false;
+                        warn!("gamepad {} disconnected", id);
+
+                        for (entity, player, input) in (&*entities, &players, &mut inputs).join() {
+                            if player.device == InputDevice::Gamepad(*id) {
+                                warn!("{} lost input", player.palette_key);
+                                *input = Input::new();
+                                lost.players.push((entity, *id));
+                            }
+                        }
                     }
                     _ => {}
                 }
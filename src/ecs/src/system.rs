@@ -3,22 +3,31 @@ mod autotile;
 mod background_animation;
 mod collision;
 mod control;
+mod delivery;
+mod effect;
 mod generate;
+mod hissatsu;
 mod input;
 mod interact;
 mod interaction;
+mod interaction_render;
 mod inventory_render;
+mod keyboard;
 mod layer;
 mod melt;
 mod movement;
+mod order_timer;
 mod orders;
+mod replay;
 mod score;
+mod stats;
 mod timer;
 //mod preparation_interaction;
 //mod topping_interaction;
 
 pub use self::{
-    animation::*, autotile::*, background_animation::*, collision::*, control::*, generate::*,
-    input::*, interact::*, interaction::*, inventory_render::*, layer::*, melt::*, movement::*,
-    orders::*, score::*, timer::*,
+    animation::*, autotile::*, background_animation::*, collision::*, control::*, delivery::*,
+    effect::*, generate::*, hissatsu::*, input::*, interact::*, interaction::*,
+    interaction_render::*, inventory_render::*, keyboard::*, layer::*, melt::*, movement::*,
+    order_timer::*, orders::*, replay::*, score::*, stats::*, timer::*,
 };
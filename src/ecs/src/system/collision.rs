@@ -1,5 +1,5 @@
 use amethyst::{
-    core::{timing::Time, transform::Transform},
+    core::transform::Transform,
     ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage},
 };
 use crate::component::*;
@@ -18,12 +18,12 @@ impl<'s> System<'s> for CollisionSystem {
         ReadStorage<'s, Velocity>,
         ReadStorage<'s, Solid>,
         ReadStorage<'s, Direction>,
-        Read<'s, Time>,
+        Read<'s, Tick>,
     );
 
     fn run(
         &mut self,
-        (entities, hitboxes, mut transforms, velocities, solids, directions, time): Self::SystemData,
+        (entities, hitboxes, mut transforms, velocities, solids, directions, tick): Self::SystemData,
     ) {
         for (e, e_hitbox, e_velocity, _) in (&*entities, &hitboxes, &velocities, !&solids).join() {
             for (o, o_hitbox, _) in (&*entities, &hitboxes, &solids).join() {
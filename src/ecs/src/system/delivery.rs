@@ -0,0 +1,88 @@
+use amethyst::{
+    assets::AssetStorage,
+    audio::{output::Output, Source},
+    core::timing::Time,
+    ecs::prelude::*,
+    renderer::Hidden,
+    shrev::{EventChannel, ReaderId},
+};
+use crate::component::*;
+use nk_data::*;
+use std::ops::Deref;
+
+const BLINK_DURATION: f32 = 1.0;
+const BLINK_RATE: f32 = 8.0;
+
+/// Plays the pickup sound for accepted deliveries and blinks the team's score when one is
+/// rejected.
+pub struct DeliveryFeedbackSystem {
+    reader: Option<ReaderId<DeliveryEvent>>,
+    blink: [f32; 2],
+}
+
+impl DeliveryFeedbackSystem {
+    pub fn new() -> Self {
+        DeliveryFeedbackSystem {
+            reader: None,
+            blink: [0.0; 2],
+        }
+    }
+}
+
+impl<'s> System<'s> for DeliveryFeedbackSystem {
+    type SystemData = (
+        Read<'s, EventChannel<DeliveryEvent>>,
+        Read<'s, Time>,
+        Read<'s, AssetStorage<Source>>,
+        Option<Read<'s, Sounds>>,
+        Option<Read<'s, Output>>,
+        Entities<'s>,
+        ReadStorage<'s, ScoreDigit>,
+        WriteStorage<'s, Hidden>,
+    );
+
+    fn setup(&mut self, mut res: &mut Resources) {
+        Self::SystemData::setup(&mut res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<DeliveryEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (channel, time, storage, sounds, output, entities, digits, mut hidden): Self::SystemData,
+    ) {
+        if let Some(ref mut reader) = &mut self.reader {
+            for event in channel.read(reader) {
+                match event.result {
+                    DeliveryResult::Accepted { .. } | DeliveryResult::Partial { .. } => {
+                        if let Some(sounds) = &sounds {
+                            play_pickup(&*sounds, &storage, output.as_ref().map(|o| o.deref()));
+                        }
+                    }
+                    DeliveryResult::Rejected { .. } => {
+                        if let Some(blink) = self.blink.get_mut(event.team) {
+                            *blink = BLINK_DURATION;
+                        }
+                    }
+                }
+            }
+        }
+
+        for blink in self.blink.iter_mut() {
+            *blink = (*blink - time.delta_seconds()).max(0.0);
+        }
+        for (entity, digit) in (&*entities, &digits).join() {
+            let blink = self.blink[digit.1 as usize];
+            let hide = blink > 0.0 && (blink * BLINK_RATE) as usize % 2 == 0;
+            if hide && !hidden.contains(entity) {
+                hidden
+                    .insert(entity, Hidden)
+                    .expect("Failed to hide score digit!");
+            } else if !hide && hidden.contains(entity) {
+                hidden.remove(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use amethyst::{
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use crate::component::*;
+use either::*;
+use log::*;
+use nk_data::*;
+use rand::{seq::SliceRandom, Rng};
+
+pub struct EffectSystem {
+    reader: Option<ReaderId<OrderEvent>>,
+    hissatsu_reader: Option<ReaderId<HissatsuEvent>>,
+}
+
+impl EffectSystem {
+    pub fn new() -> Self {
+        EffectSystem {
+            reader: None,
+            hissatsu_reader: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for EffectSystem {
+    type SystemData = (
+        Read<'s, EventChannel<OrderEvent>>,
+        Read<'s, EventChannel<HissatsuEvent>>,
+        Read<'s, Definitions>,
+        Write<'s, Match>,
+        WriteStorage<'s, Player>,
+        WriteStorage<'s, Statuses>,
+        Write<'s, MatchRng>,
+        Read<'s, Tick>,
+    );
+
+    fn setup(&mut self, mut res: &mut Resources) {
+        Self::SystemData::setup(&mut res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<OrderEvent>>()
+                .register_reader(),
+        );
+        self.hissatsu_reader = Some(
+            res.fetch_mut::<EventChannel<HissatsuEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (
+            channel,
+            hissatsu_channel,
+            defs,
+            mut match_data,
+            mut players,
+            mut statuses,
+            mut match_rng,
+            tick,
+        ): Self::SystemData,
+    ) {
+        let ds = tick.delta_seconds();
+        let mut rng = &mut *match_rng;
+
+        for (player, status) in (&players, &mut statuses).join() {
+            let carrying = match player.inventory {
+                Some(Either::Right(_)) => true,
+                _ => false,
+            };
+            status.update(ds, carrying);
+        }
+        for team in match_data.teams.iter_mut() {
+            team.update_modifiers(ds);
+        }
+
+        let events = if let Some(ref mut reader) = &mut self.reader {
+            channel.read(reader).cloned().collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        for event in events {
+            match event {
+                OrderEvent::Pickup { player, .. } => {
+                    let effects = match players.get(player).and_then(|p| p.inventory.as_ref()) {
+                        Some(Either::Right(order)) => order.active_effects(&defs),
+                        _ => continue,
+                    };
+                    self.apply_carrier(&effects, player, &mut players, &mut statuses, &mut rng);
+                }
+                OrderEvent::Topping {
+                    player, topping, ..
+                } => {
+                    let negated = match players.get(player).and_then(|p| p.inventory.as_ref()) {
+                        Some(Either::Right(order)) => order.is_negated(&defs),
+                        _ => continue,
+                    };
+                    if negated {
+                        continue;
+                    }
+                    let effects = match defs.get_topping(&topping) {
+                        Some(t) => t.effect.to_owned(),
+                        None => continue,
+                    };
+                    self.apply_carrier(&effects, player, &mut players, &mut statuses, &mut rng);
+                }
+                OrderEvent::Delivery { team, order, .. } => {
+                    for effect in order.active_effects(&defs).iter() {
+                        self.apply_team(
+                            effect,
+                            team,
+                            Some(DEFAULT_EFFECT_DURATION),
+                            &mut match_data,
+                            &mut statuses,
+                            &mut rng,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let specials = if let Some(ref mut reader) = &mut self.hissatsu_reader {
+            hissatsu_channel.read(reader).cloned().collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        for HissatsuEvent { team, hissatsu, .. } in specials {
+            if let Some(def) = defs.get_hissatsu(&hissatsu) {
+                self.apply_hissatsu(
+                    def,
+                    team,
+                    &mut match_data,
+                    &mut players,
+                    &mut statuses,
+                    &mut rng,
+                );
+            }
+        }
+    }
+}
+
+impl EffectSystem {
+    fn apply_carrier<R: Rng>(
+        &self,
+        effects: &[EffectDefinition],
+        carrier: Entity,
+        players: &mut WriteStorage<Player>,
+        statuses: &mut WriteStorage<Statuses>,
+        rng: &mut R,
+    ) {
+        for effect in effects.iter() {
+            match effect {
+                EffectDefinition::OrderMeltTimer(v) => {
+                    if let Some(Either::Right(order)) =
+                        players.get_mut(carrier).and_then(|p| p.inventory.as_mut())
+                    {
+                        order.scale_delivery(v.sample(rng));
+                    }
+                }
+                EffectDefinition::Speed(EffectFilter::Carrier, v, d) => {
+                    if let Some(s) = statuses.get_mut(carrier) {
+                        s.push(Status {
+                            kind: StatusKind::Speed(v.sample(rng)),
+                            timer: d.sample(rng),
+                            carrier: true,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Effects that name a team are resolved from the team using the special, the others land
+    /// on the special's target.
+    fn apply_hissatsu<R: Rng>(
+        &self,
+        def: &HissatsuDef,
+        team: usize,
+        match_data: &mut Match,
+        players: &mut WriteStorage<Player>,
+        statuses: &mut WriteStorage<Statuses>,
+        rng: &mut R,
+    ) {
+        let targets = team_sides(match_data.teams.len(), team, &def.target);
+        let duration = def.duration.sample(rng);
+        for effect in def.effect.iter() {
+            match effect {
+                EffectDefinition::Speed(EffectFilter::Carrier, v, d) => {
+                    for t in targets.iter() {
+                        for member in match_data.teams[*t].members() {
+                            if let Some(s) = statuses.get_mut(member) {
+                                s.push(Status {
+                                    kind: StatusKind::Speed(v.sample(rng)),
+                                    timer: d.sample(rng),
+                                    carrier: false,
+                                });
+                            }
+                        }
+                    }
+                }
+                EffectDefinition::OrderMeltTimer(v) => {
+                    // Every order hit by the special changes by the same factor.
+                    let factor = v.sample(rng);
+                    for t in targets.iter() {
+                        for member in match_data.teams[*t].members() {
+                            if let Some(Either::Right(order)) =
+                                players.get_mut(member).and_then(|p| p.inventory.as_mut())
+                            {
+                                order.scale_delivery(factor);
+                            }
+                        }
+                        for order in match_data.teams[*t].orders.iter_mut() {
+                            order.scale_timer(factor);
+                        }
+                    }
+                }
+                EffectDefinition::BlockSpecial(d) => {
+                    for t in targets.iter() {
+                        match_data.teams[*t].modifiers.push(TeamModifier {
+                            kind: TeamModifierKind::BlockSpecial,
+                            timer: d.sample(rng),
+                        });
+                    }
+                }
+                _ => self.apply_team(effect, team, duration, match_data, statuses, rng),
+            }
+        }
+    }
+
+    fn apply_team<R: Rng>(
+        &self,
+        effect: &EffectDefinition,
+        team: usize,
+        duration: Option<f32>,
+        match_data: &mut Match,
+        statuses: &mut WriteStorage<Statuses>,
+        rng: &mut R,
+    ) {
+        let count = match_data.teams.len();
+        let sides = |side: &TeamSide| team_sides(count, team, side);
+        match effect {
+            EffectDefinition::Speed(EffectFilter::Team(side, quantity), v, d) => {
+                for t in sides(side) {
+                    let members = self.select_members(&match_data.teams[t], quantity, rng);
+                    for member in members {
+                        if let Some(s) = statuses.get_mut(member) {
+                            s.push(Status {
+                                kind: StatusKind::Speed(v.sample(rng)),
+                                timer: d.sample(rng),
+                                carrier: false,
+                            });
+                        }
+                    }
+                }
+            }
+            EffectDefinition::GlobalMeltSpeed(side, v) => {
+                for t in sides(side) {
+                    match_data.teams[t].modifiers.push(TeamModifier {
+                        kind: TeamModifierKind::MeltSpeed(v.sample(rng)),
+                        timer: duration,
+                    });
+                }
+            }
+            EffectDefinition::PowerMeterFlatBonus(side, v) => {
+                for t in sides(side) {
+                    let team = &mut match_data.teams[t];
+                    team.power_meter = (team.power_meter + v.sample(rng)).max(0.0).min(1.0);
+                }
+            }
+            EffectDefinition::BlockSpecial(d) => {
+                for t in sides(&TeamSide::Adversary) {
+                    match_data.teams[t].modifiers.push(TeamModifier {
+                        kind: TeamModifierKind::BlockSpecial,
+                        timer: d.sample(rng),
+                    });
+                }
+            }
+            EffectDefinition::Screen(screen, side, d) => {
+                for t in sides(side) {
+                    info!("screen effect {:?} on team {}", screen, t);
+                    match_data.teams[t].modifiers.push(TeamModifier {
+                        kind: TeamModifierKind::Screen(screen.clone()),
+                        timer: d.sample(rng),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select_members<R: Rng>(
+        &self,
+        team: &Team,
+        quantity: &FilterQuantity,
+        rng: &mut R,
+    ) -> Vec<Entity> {
+        match quantity {
+            FilterQuantity::WholeTeam => team.members(),
+            FilterQuantity::One(a) => team.member(a).into_iter().collect(),
+            FilterQuantity::Two(a, b) => [a, b].iter().filter_map(|m| team.member(m)).collect(),
+            FilterQuantity::Three(a, b, c) => {
+                [a, b, c].iter().filter_map(|m| team.member(m)).collect()
+            }
+            FilterQuantity::Random(count) => {
+                let count = match count {
+                    MemberCount::One => 1,
+                    MemberCount::Two => 2,
+                    MemberCount::Three => 3,
+                };
+                team.members()
+                    .choose_multiple(rng, count)
+                    .cloned()
+                    .collect()
+            }
+        }
+    }
+}
+
+fn team_sides(count: usize, team: usize, side: &TeamSide) -> Vec<usize> {
+    (0..count)
+        .filter(|i| match side {
+            TeamSide::Partner => *i == team,
+            TeamSide::Adversary => *i != team,
+        })
+        .collect()
+}
@@ -1,39 +1,49 @@
-use amethyst::{
-    core::timing::Time,
-    ecs::prelude::{Read, System, Write},
-};
+use amethyst::ecs::prelude::{Read, System, Write};
 use log::*;
 use nk_data::*;
-use rand::{seq::SliceRandom, Rng};
+use rand::seq::SliceRandom;
 
 pub struct GenerateSystem;
 
-const STEP: f32 = 20.0;
-
 impl<'s> System<'s> for GenerateSystem {
-    type SystemData = (Read<'s, Definitions>, Write<'s, Match>, Read<'s, Time>);
-
-    fn run(&mut self, (defs, mut match_data, time): Self::SystemData) {
-        let mut rng = rand::thread_rng();
-
-        match_data.order_gen_timer += time.delta_seconds();
-        while match_data.order_gen_timer >= STEP {
+    type SystemData = (
+        Read<'s, Definitions>,
+        Write<'s, Match>,
+        Write<'s, MatchRng>,
+        Read<'s, Tick>,
+    );
+
+    fn run(&mut self, (defs, mut match_data, mut match_rng, tick): Self::SystemData) {
+        let mut rng = &mut *match_rng;
+
+        match_data.order_gen_timer -= tick.delta_seconds();
+        while match_data.order_gen_timer <= 0.0 {
+            let profile = match_data.generation.clone();
+            let elapsed = match_data.elapsed;
             let g_flavors = match_data.flavors.to_owned();
             let g_preparations = match_data.preparations.to_owned();
             let g_toppings = match_data.toppings.to_owned();
 
             for team in match_data.teams.iter_mut() {
+                if team.orders.len() >= profile.max_outstanding {
+                    continue;
+                }
+
+                // Sorted first, `dedup` only drops repeats that are next to each other.
                 let mut flavors = team.flavors.to_owned();
                 flavors.append(&mut g_flavors.to_owned());
+                flavors.sort_unstable();
                 flavors.dedup();
 
                 let mut preparations = team.preparations.to_owned();
                 preparations.append(&mut g_preparations.to_owned());
+                preparations.sort_unstable();
                 preparations.dedup();
                 preparations.shuffle(&mut rng);
 
                 let mut toppings = team.toppings.to_owned();
                 toppings.append(&mut g_toppings.to_owned());
+                toppings.sort_unstable();
                 toppings.dedup();
                 toppings.shuffle(&mut rng);
 
@@ -41,288 +51,71 @@ impl<'s> System<'s> for GenerateSystem {
                     panic!("NO PREPARATIONS IN MATCH+TEAM!");
                 };
 
-                if toppings.len() < 1 {
-                    panic!("NO TOPPINGS IN MATCH+TEAM!");
-                };
-
                 if flavors.len() < 1 {
                     panic!("NO FLAVORS IN MATCH+TEAM!");
                 };
-                let preparation = &defs
-                    .preparations()
-                    .find(|x| x.index == preparations[0])
-                    .unwrap();
-
-                let rand = match preparation.max_scoops {
-                    1 => match preparation.takes_topping {
-                        true => match rng.gen_range(0, 2) {
-                            0 => 0,
-                            1 => 4,
-                            _ => std::unreachable!(),
-                        },
-                        false => 0,
-                    },
-                    2 => match preparation.takes_topping {
-                        true => match rng.gen_range(0, 4) {
-                            0 => 0,
-                            1 => 1,
-                            2 => 4,
-                            3 => 5,
-                            _ => std::unreachable!(),
-                        },
-                        false => match rng.gen_range(0, 2) {
-                            0 => 0,
-                            1 => 1,
-                            _ => std::unreachable!(),
-                        },
-                    },
-                    3 => match preparation.takes_topping {
-                        true => match rng.gen_range(0, 6) {
-                            0 => 0,
-                            1 => 1,
-                            2 => 2,
-                            3 => 4,
-                            4 => 5,
-                            5 => 6,
-                            _ => std::unreachable!(),
-                        },
-                        false => match rng.gen_range(0, 3) {
-                            0 => 0,
-                            1 => 1,
-                            2 => 2,
-                            _ => std::unreachable!(),
-                        },
-                    },
-                    4 => match preparation.takes_topping {
-                        true => rng.gen_range(0, 7),
-                        false => rng.gen_range(0, 4),
-                    },
-                    _ => std::unreachable!(),
-                };
-
-                // 0 => 1f 0t
-                // 1 => 2f 0t
-                // 2 => 3f 0t
-                // 3 => 4f 0t
-                // 4 => 1f 1t
-                // 5 => 2f 1t
-                // 6 => 3f 1t
-                let new_order = match rand {
-                    0 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[0], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        OrderPossibility::OneFlavorNoTopping(f1.1.clone())
-                    }
-                    1 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[1], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f2 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        OrderPossibility::TwoFlavorsNoTopping(f1.1.clone(), f2.1.clone())
+                let preparation = match defs.get_preparation(&preparations[0]) {
+                    Some(p) => p,
+                    None => {
+                        warn!("UNKNOWN PREPARATION {:?} IN MATCH+TEAM!", preparations[0]);
+                        continue;
                     }
-                    2 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[2], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f2 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f3 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        OrderPossibility::ThreeFlavorsNoTopping(
-                            f1.1.clone(),
-                            f2.1.clone(),
-                            f3.1.clone(),
-                        )
-                    }
-                    3 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[3], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f2 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f3 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f4 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        OrderPossibility::FourFlavorsNoTopping(
-                            f1.1.clone(),
-                            f2.1.clone(),
-                            f3.1.clone(),
-                            f4.1.clone(),
-                        )
-                    }
-                    4 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[4], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        OrderPossibility::OneFlavorWithTopping(f1.1.clone(), toppings[0].clone())
-                    }
-                    5 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[5], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f2 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
+                };
 
-                        OrderPossibility::TwoFlavorsWithTopping(
-                            f1.1.clone(),
-                            f2.1.clone(),
-                            toppings[0].clone(),
-                        )
+                // A match without toppings only gets shapes that take none.
+                let shapes = preparation
+                    .shapes()
+                    .into_iter()
+                    .filter(|s| s.toppings <= toppings.len())
+                    .map(|s| (profile.shape_weight(&s, elapsed), s))
+                    .collect::<Vec<_>>();
+                let shape = match shapes.choose_weighted(&mut rng, |item| item.0) {
+                    Ok((_, shape)) => shape.clone(),
+                    Err(_) => {
+                        // Profile has no weight for any shape this preparation accepts.
+                        shapes.choose(&mut rng).expect("IMPOSSIBURU").1.clone()
                     }
-                    6 => {
-                        let mut selection = flavors
-                            .into_iter()
-                            .map(|f| (defs.flavors().find(|e| e.index == f).unwrap().weights[6], f))
-                            .collect::<Vec<(usize, FlavorIndex)>>();
-
-                        let f1 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f2 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
-
-                        let f3 = {
-                            let my_selection = selection.to_owned();
-                            let key = my_selection
-                                .choose_weighted(&mut rng, |item| item.0)
-                                .unwrap();
-                            selection.remove_item(&key).unwrap()
-                        };
+                };
 
-                        OrderPossibility::ThreeFlavorsWithTopping(
-                            f1.1.clone(),
-                            f2.1.clone(),
-                            f3.1.clone(),
-                            toppings[0].clone(),
-                        )
+                let mut selection = flavors
+                    .into_iter()
+                    .map(|f| (profile.flavor_weight(&f), f))
+                    .collect::<Vec<(f32, FlavorIndex)>>();
+
+                let mut picked = Vec::with_capacity(shape.scoops);
+                for _ in 0..shape.scoops {
+                    // Flavors are picked without repetition while there are enough of them.
+                    if selection.is_empty() {
+                        let f = picked.choose(&mut rng).cloned().unwrap();
+                        picked.push(f);
+                        continue;
                     }
-                    _ => std::unreachable!(),
-                };
+                    let key = match selection.choose_weighted(&mut rng, |item| item.0) {
+                        Ok((_, f)) => f.clone(),
+                        Err(_) => {
+                            // Profile has no weight for any flavor left.
+                            selection.choose(&mut rng).expect("IMPOSSIBURU").1.clone()
+                        }
+                    };
+                    let i = selection.iter().position(|x| x.1 == key).unwrap();
+                    picked.push(selection.remove(i).1);
+                }
+
+                let new_order = Recipe::new(
+                    picked,
+                    toppings.iter().take(shape.toppings).cloned().collect(),
+                );
 
                 info!("gen new order: {:?}", new_order);
 
                 team.orders.push(OrderDefinition::new(
                     new_order,
                     preparations[0].clone(),
-                    45.0,
+                    profile.order_timer_at(elapsed),
                 ));
             }
 
-            match_data.order_gen_timer -= STEP;
+            match_data.order_gen_timer += profile.interval_at(elapsed);
         }
     }
 }
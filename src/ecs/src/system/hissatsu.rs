@@ -0,0 +1,91 @@
+use amethyst::{
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use crate::component::*;
+use log::*;
+use nk_data::*;
+
+/// Fills the power meter from deliveries and spends it when a player triggers the team's special.
+/// The effects themselves are applied by `EffectSystem` when it reads the `HissatsuEvent`.
+pub struct HissatsuSystem {
+    reader: Option<ReaderId<OrderEvent>>,
+}
+
+impl HissatsuSystem {
+    pub fn new() -> Self {
+        HissatsuSystem { reader: None }
+    }
+}
+
+impl<'s> System<'s> for HissatsuSystem {
+    type SystemData = (
+        Read<'s, EventChannel<OrderEvent>>,
+        Write<'s, EventChannel<HissatsuEvent>>,
+        Read<'s, Definitions>,
+        Write<'s, Match>,
+        Entities<'s>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Input>,
+    );
+
+    fn setup(&mut self, mut res: &mut Resources) {
+        Self::SystemData::setup(&mut res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<OrderEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (channel, mut hissatsu_channel, defs, mut match_data, entities, players, mut inputs): Self::SystemData,
+    ) {
+        if let Some(ref mut reader) = &mut self.reader {
+            for event in channel.read(reader) {
+                if let OrderEvent::Delivery { team, order, .. } = event {
+                    if let Some(team) = match_data.teams.get_mut(*team) {
+                        let gain = POWER_METER_DELIVERY_GAIN * order.ingredient_count() as f32;
+                        team.power_meter = (team.power_meter + gain).min(1.0);
+                    }
+                }
+            }
+        }
+
+        for (entity, player, input) in (&*entities, &players, &mut inputs).join() {
+            if !input.wants_special {
+                continue;
+            }
+            // Holding the button down does not fire the special again.
+            input.wants_special = false;
+
+            let team = match match_data.teams.get_mut(player.team_index) {
+                Some(team) => team,
+                None => continue,
+            };
+            let def = match team.hissatsu.as_ref().and_then(|h| defs.get_hissatsu(h)) {
+                Some(def) => def,
+                None => continue,
+            };
+            if !team.can_use_special(def.cost) {
+                info!(
+                    "team {} cannot use {} (meter {:.2}/{:.2}, blocked {})",
+                    player.team_index,
+                    def.key,
+                    team.power_meter,
+                    def.cost,
+                    team.special_blocked()
+                );
+                continue;
+            }
+
+            team.power_meter -= def.cost;
+            info!("team {} used {}", player.team_index, def.key);
+            hissatsu_channel.single_write(HissatsuEvent {
+                player: entity,
+                team: player.team_index,
+                hissatsu: def.index.clone(),
+            });
+        }
+    }
+}
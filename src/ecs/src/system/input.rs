@@ -4,13 +4,32 @@ use crate::component::*;
 pub struct InputSystem;
 
 impl<'s> System<'s> for InputSystem {
-    type SystemData = (ReadStorage<'s, Input>, WriteStorage<'s, Velocity>);
+    type SystemData = (
+        ReadStorage<'s, Input>,
+        ReadStorage<'s, Statuses>,
+        WriteStorage<'s, Velocity>,
+        WriteStorage<'s, Direction>,
+    );
 
-    fn run(&mut self, (inputs, mut velocities): Self::SystemData) {
-        for (input, velocity) in (&inputs, &mut velocities).join() {
+    fn run(&mut self, (inputs, statuses, mut velocities, mut directions): Self::SystemData) {
+        for (input, status, velocity, direction) in (
+            &inputs,
+            statuses.maybe(),
+            &mut velocities,
+            (&mut directions).maybe(),
+        )
+            .join()
+        {
             if let Some(dir) = input.wants_to_move {
-                velocity.current[0] = velocity.velocity[0] * dir.get_x();
-                velocity.current[1] = velocity.velocity[1] * dir.get_y();
+                let multiplier = status.map(|s| s.speed_multiplier()).unwrap_or(1.0);
+                velocity.current[0] = velocity.velocity[0] * multiplier * dir.get_x();
+                velocity.current[1] = velocity.velocity[1] * multiplier * dir.get_y();
+
+                // Facing follows the movement input so replayed and simulated players turn too.
+                if let Some(direction) = direction {
+                    direction.previous = Some(direction.current);
+                    direction.current = dir;
+                }
             }
         }
     }
@@ -1,11 +1,7 @@
 use amethyst::{
-    assets::AssetStorage,
-    audio::{output::Output, Source},
     core::transform::{GlobalTransform, Parent, Transform},
-    ecs::prelude::{
-        Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage,
-    },
-    renderer::{Hidden, SpriteRender, Transparent},
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage},
+    shrev::EventChannel,
 };
 use crate::component::*;
 use either::*;
@@ -18,16 +14,9 @@ use ncollide2d::{
 };
 use nk_data::*;
 use shred_derive::*;
-use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
-};
 
 pub struct InteractSystem;
 
-type Palette = HashMap<String, image::Rgba<u8>>;
-type ArcMutPalette = Arc<Mutex<Palette>>;
-
 #[derive(SystemData)]
 pub struct InteractSystemData<'s> {
     entities: Entities<'s>,
@@ -39,28 +28,15 @@ pub struct InteractSystemData<'s> {
     directions: ReadStorage<'s, Direction>,
     interacts: WriteStorage<'s, Interact>,
     tables: WriteStorage<'s, Table>,
-    sprites: WriteStorage<'s, SpriteRender>,
     flavor_interactions: WriteStorage<'s, FlavorInteraction>,
     preparation_interactions: WriteStorage<'s, PreparationInteraction>,
     topping_interactions: WriteStorage<'s, ToppingInteraction>,
     parents: WriteStorage<'s, Parent>,
-    hiddens: WriteStorage<'s, Hidden>,
-    transparents: WriteStorage<'s, Transparent>,
-    start_pieces: WriteStorage<'s, StartBarPiece>,
-    middle_pieces: WriteStorage<'s, MiddleBarPiece>,
-    end_pieces: WriteStorage<'s, EndBarPiece>,
-    backgrounds: WriteStorage<'s, BarBackground>,
-    foregrounds: WriteStorage<'s, BarForeground>,
-    hold_keys: WriteStorage<'s, HoldKey>,
-    sequence_keys: WriteStorage<'s, SequenceKey>,
-    alternative_keys: WriteStorage<'s, AlternativeKey>,
-    anims: Read<'s, Animations>,
     defs: Read<'s, Definitions>,
-    palette: Read<'s, ArcMutPalette>,
     match_data: Write<'s, Match>,
-    storage: Read<'s, AssetStorage<Source>>,
-    sounds: ReadExpect<'s, Sounds>,
-    audio_output: Option<Read<'s, Output>>,
+    rng: Write<'s, MatchRng>,
+    order_events: Write<'s, EventChannel<OrderEvent>>,
+    delivery_events: Write<'s, EventChannel<DeliveryEvent>>,
 }
 
 impl<'s> System<'s> for InteractSystem {
@@ -78,28 +54,15 @@ impl<'s> System<'s> for InteractSystem {
             directions,
             mut interacts,
             mut tables,
-            mut sprites,
             mut flavor_interactions,
             mut preparation_interactions,
             mut topping_interactions,
             mut parents,
-            mut hiddens,
-            mut transparents,
-            mut start_pieces,
-            mut middle_pieces,
-            mut end_pieces,
-            mut backgrounds,
-            mut foregrounds,
-            mut hold_keys,
-            mut sequence_keys,
-            mut alternative_keys,
-            anims,
             defs,
-            palette,
             mut match_data,
-            storage,
-            sounds,
-            audio_output,
+            mut rng,
+            mut order_events,
+            mut delivery_events,
         }: Self::SystemData,
     ) {
         // Reset interaction highlight
@@ -160,7 +123,8 @@ impl<'s> System<'s> for InteractSystem {
                             Cardinal::East => bt.translation.x.partial_cmp(&at.translation.x),
                         }
                         .unwrap()
-                    }) {
+                    })
+                {
                     // If not already in use by someone else.
                     if let None = interact.highlighted_by {
                         // Make positions for comparison
@@ -186,7 +150,7 @@ impl<'s> System<'s> for InteractSystem {
                             interact_shape,
                             1.0,
                         ) {
-                            interact.highlighted_by = Some(player.gamepad_index);
+                            interact.highlighted_by = Some(player_entity);
                             //top.sprite_number = interact.highlight;
                             info!("CAN INTERACT!");
                             if input.wants_to_interact {
@@ -202,22 +166,11 @@ impl<'s> System<'s> for InteractSystem {
                                                     &entities,
                                                     &mut flavor_interactions,
                                                     &mut parents,
-                                                    &mut hiddens,
-                                                    &mut transparents,
                                                     &mut transforms,
                                                     &mut global_transforms,
-                                                    &mut sprites,
-                                                    &mut start_pieces,
-                                                    &mut middle_pieces,
-                                                    &mut end_pieces,
-                                                    &mut backgrounds,
-                                                    &mut foregrounds,
-                                                    &mut hold_keys,
-                                                    &anims,
+                                                    &mut rng,
                                                     flavor,
                                                     player_entity,
-                                                    player.palette_key.clone(),
-                                                    &player.gamepad_style,
                                                 );
                                             player.interaction = Some(interaction_entity);
                                         }
@@ -231,7 +184,7 @@ impl<'s> System<'s> for InteractSystem {
                                                     // Insert scoops into order if possible.
                                                     if table.has_order() {
                                                         let mut order = table.extract_order();
-                                                        if order.ingredient_count() > 3 {
+                                                        if !order.can_insert_flavor(&defs) {
                                                             info!(
                                                         "ORDER IS FULL, CANNOT INSERT MORE FLAVORS"
                                                     );
@@ -267,21 +220,10 @@ impl<'s> System<'s> for InteractSystem {
                                                             &entities,
                                                             &mut preparation_interactions,
                                                             &mut parents,
-                                                            &mut hiddens,
-                                                            &mut transparents,
                                                             &mut transforms,
                                                             &mut global_transforms,
-                                                            &mut sprites,
-                                                            &mut start_pieces,
-                                                            &mut middle_pieces,
-                                                            &mut end_pieces,
-                                                            &mut backgrounds,
-                                                            &mut foregrounds,
-                                                            &mut sequence_keys,
-                                                            &anims,
+                                                            &mut rng,
                                                             player_entity,
-                                                            player.palette_key.clone(),
-                                                            &player.gamepad_style,
                                                             interact.top,
                                                         );
                                                     player.interaction = Some(interaction_entity);
@@ -303,34 +245,21 @@ impl<'s> System<'s> for InteractSystem {
                                                     // less items) and order is completed (prepared).
                                                     if order.completed {
                                                         info!("ORDER IS COMPLETED, CANNOT GO BACK IN!");
-                                                        if order.ingredient_count() > 3 {
+                                                        if !order.can_insert_topping(&defs) {
                                                             info!(
                                                             "ORDER IS FULL, CANNOT INSERT TOPPING"
                                                         );
-                                                        } else if order.has_topping() {
-                                                            info!("ORDER ALREADY HAS TOPPING");
                                                         } else {
                                                             let interaction_entity = self
                                                                 .create_topping_interaction(
                                                                     &entities,
                                                                     &mut topping_interactions,
                                                                     &mut parents,
-                                                                    &mut hiddens,
-                                                                    &mut transparents,
                                                                     &mut transforms,
                                                                     &mut global_transforms,
-                                                                    &mut sprites,
-                                                                    &mut start_pieces,
-                                                                    &mut middle_pieces,
-                                                                    &mut end_pieces,
-                                                                    &mut backgrounds,
-                                                                    &mut foregrounds,
-                                                                    &mut alternative_keys,
-                                                                    &anims,
+                                                                    &mut rng,
                                                                     topping,
                                                                     player_entity,
-                                                                    player.palette_key.clone(),
-                                                                    &player.gamepad_style,
                                                                 );
                                                             player.interaction =
                                                                 Some(interaction_entity);
@@ -348,26 +277,53 @@ impl<'s> System<'s> for InteractSystem {
                                             &mut player.inventory
                                         {
                                             info!("DELIVERED {:#?}!", order);
-                                            let mut my_team =
-                                                &mut match_data.teams[player.team_index];
-
-                                            let mut rem = None;
-                                            for (i, o) in my_team
-                                                .orders
-                                                .iter()
-                                                .enumerate()
-                                                .take_while(|(i, _)| *i < 4)
-                                            {
-                                                if o.matches(&order) {
-                                                    my_team.score += order.calculate_worth(&defs);
-                                                    rem = Some(i);
-                                                    break;
+                                            let rules = match_data.generation.delivery.clone();
+                                            let result = match_data.teams[player.team_index]
+                                                .deliver(&order, &rules, &defs);
+                                            info!("DELIVERY RESULT {:?}", result);
+                                            delivery_events.single_write(DeliveryEvent {
+                                                player: player_entity,
+                                                team: player.team_index,
+                                                result: result.clone(),
+                                            });
+                                            match result {
+                                                DeliveryResult::Accepted { score } => {
+                                                    order_events.single_write(
+                                                        OrderEvent::Delivery {
+                                                            player: player_entity,
+                                                            team: player.team_index,
+                                                            order: order.clone(),
+                                                            score,
+                                                        },
+                                                    );
+                                                    None
+                                                }
+                                                DeliveryResult::Partial { score, .. } => {
+                                                    order_events.single_write(
+                                                        OrderEvent::PartialDelivery {
+                                                            player: player_entity,
+                                                            team: player.team_index,
+                                                            order: order.clone(),
+                                                            score,
+                                                        },
+                                                    );
+                                                    None
+                                                }
+                                                DeliveryResult::Rejected { returned, .. } => {
+                                                    order_events.single_write(
+                                                        OrderEvent::WrongDelivery {
+                                                            player: player_entity,
+                                                            team: player.team_index,
+                                                            order: order.clone(),
+                                                        },
+                                                    );
+                                                    if returned {
+                                                        Some(Either::Right(order.clone()))
+                                                    } else {
+                                                        None
+                                                    }
                                                 }
                                             }
-                                            if let Some(i) = rem {
-                                                my_team.orders.remove(i);
-                                            }
-                                            None
                                         } else if let Some(Either::Left(flavor)) =
                                             &mut player.inventory
                                         {
@@ -399,70 +355,22 @@ impl<'s> InteractSystem {
         entities: &Entities<'s>,
         mut flavor_interactions: &mut WriteStorage<'s, FlavorInteraction>,
         mut parents: &mut WriteStorage<'s, Parent>,
-        mut hiddens: &mut WriteStorage<'s, Hidden>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
         mut transforms: &mut WriteStorage<'s, Transform>,
         mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut start_pieces: &mut WriteStorage<'s, StartBarPiece>,
-        mut middle_pieces: &mut WriteStorage<'s, MiddleBarPiece>,
-        mut end_pieces: &mut WriteStorage<'s, EndBarPiece>,
-        mut backgrounds: &mut WriteStorage<'s, BarBackground>,
-        mut foregrounds: &mut WriteStorage<'s, BarForeground>,
-        mut hold_keys: &mut WriteStorage<'s, HoldKey>,
-        anims: &Read<'s, Animations>,
+        rng: &mut MatchRng,
         flavor: FlavorIndex,
         player: Entity,
-        key: String,
-        style: &Style,
     ) -> Entity {
-        let mut entity_transform = Transform::default();
-        entity_transform.translation.y = 24.0;
-        entity_transform.scale.x = 0.5;
-        entity_transform.scale.y = 0.5;
-
-        let interaction = FlavorInteraction::new(2.5, flavor.clone());
-        let key_anim = interaction.key.get_str(style);
-        let entity = entities
+        entities
             .build_entity()
-            .with(interaction, &mut flavor_interactions)
+            .with(
+                FlavorInteraction::new(2.5, flavor, rng),
+                &mut flavor_interactions,
+            )
             .with(Parent { entity: player }, &mut parents)
-            .with(entity_transform, &mut transforms)
+            .with(Self::interaction_transform(), &mut transforms)
             .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
-
-        self.create_bar(
-            &entities,
-            &mut parents,
-            &mut hiddens,
-            &mut transparents,
-            &mut transforms,
-            &mut global_transforms,
-            &mut sprites,
-            &mut start_pieces,
-            &mut middle_pieces,
-            &mut end_pieces,
-            &mut backgrounds,
-            &mut foregrounds,
-            &anims,
-            entity,
-            key,
-        );
-
-        self.create_hold_key(
-            entities,
-            &mut parents,
-            &mut transparents,
-            &mut transforms,
-            &mut global_transforms,
-            &mut sprites,
-            &mut hold_keys,
-            anims,
-            entity,
-            key_anim,
-        );
-
-        entity
+            .build()
     }
 
     fn create_preparation_interaction(
@@ -470,80 +378,22 @@ impl<'s> InteractSystem {
         entities: &Entities<'s>,
         mut preparation_interactions: &mut WriteStorage<'s, PreparationInteraction>,
         mut parents: &mut WriteStorage<'s, Parent>,
-        mut hiddens: &mut WriteStorage<'s, Hidden>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
         mut transforms: &mut WriteStorage<'s, Transform>,
         mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut start_pieces: &mut WriteStorage<'s, StartBarPiece>,
-        mut middle_pieces: &mut WriteStorage<'s, MiddleBarPiece>,
-        mut end_pieces: &mut WriteStorage<'s, EndBarPiece>,
-        mut backgrounds: &mut WriteStorage<'s, BarBackground>,
-        mut foregrounds: &mut WriteStorage<'s, BarForeground>,
-        mut sequence_keys: &mut WriteStorage<'s, SequenceKey>,
-        anims: &Read<'s, Animations>,
+        rng: &mut MatchRng,
         player: Entity,
-        key: String,
-        style: &Style,
         table: Entity,
     ) -> Entity {
-        let mut entity_transform = Transform::default();
-        entity_transform.translation.y = 24.0;
-        entity_transform.scale.x = 0.5;
-        entity_transform.scale.y = 0.5;
-
-        let interaction = PreparationInteraction::new(table, 2);
-        let first = interaction.current_key(0, style);
-        let second = interaction.current_key(1, style);
-        let third = interaction.current_key(2, style);
-        let fourth = interaction.current_key(3, style);
-
-        let entity = entities
+        entities
             .build_entity()
-            .with(interaction, &mut preparation_interactions)
+            .with(
+                PreparationInteraction::new(table, 2, rng),
+                &mut preparation_interactions,
+            )
             .with(Parent { entity: player }, &mut parents)
-            .with(entity_transform, &mut transforms)
+            .with(Self::interaction_transform(), &mut transforms)
             .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
-
-        self.create_bar(
-            &entities,
-            &mut parents,
-            &mut hiddens,
-            &mut transparents,
-            &mut transforms,
-            &mut global_transforms,
-            &mut sprites,
-            &mut start_pieces,
-            &mut middle_pieces,
-            &mut end_pieces,
-            &mut backgrounds,
-            &mut foregrounds,
-            &anims,
-            entity,
-            key,
-        );
-
-        vec![first, second, third, fourth]
-            .iter()
-            .enumerate()
-            .for_each(|(i, v)| {
-                self.create_sequence_key(
-                    entities,
-                    &mut parents,
-                    &mut transparents,
-                    &mut transforms,
-                    &mut global_transforms,
-                    &mut sprites,
-                    &mut sequence_keys,
-                    anims,
-                    entity,
-                    v,
-                    i,
-                )
-            });
-
-        entity
+            .build()
     }
 
     fn create_topping_interaction(
@@ -551,232 +401,30 @@ impl<'s> InteractSystem {
         entities: &Entities<'s>,
         mut topping_interactions: &mut WriteStorage<'s, ToppingInteraction>,
         mut parents: &mut WriteStorage<'s, Parent>,
-        mut hiddens: &mut WriteStorage<'s, Hidden>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
         mut transforms: &mut WriteStorage<'s, Transform>,
         mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut start_pieces: &mut WriteStorage<'s, StartBarPiece>,
-        mut middle_pieces: &mut WriteStorage<'s, MiddleBarPiece>,
-        mut end_pieces: &mut WriteStorage<'s, EndBarPiece>,
-        mut backgrounds: &mut WriteStorage<'s, BarBackground>,
-        mut foregrounds: &mut WriteStorage<'s, BarForeground>,
-        mut alternative_keys: &mut WriteStorage<'s, AlternativeKey>,
-        anims: &Read<'s, Animations>,
+        rng: &mut MatchRng,
         topping: ToppingIndex,
         player: Entity,
-        key: String,
-        style: &Style,
     ) -> Entity {
-        let mut entity_transform = Transform::default();
-        entity_transform.translation.y = 24.0;
-        entity_transform.scale.x = 0.5;
-        entity_transform.scale.y = 0.5;
-
-        let interaction = ToppingInteraction::new(topping);
-        let left = interaction.current_key(false, style);
-        let right = interaction.current_key(true, style);
-
-        let entity = entities
-            .build_entity()
-            .with(interaction, &mut topping_interactions)
-            .with(Parent { entity: player }, &mut parents)
-            .with(entity_transform, &mut transforms)
-            .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
-
-        self.create_bar(
-            &entities,
-            &mut parents,
-            &mut hiddens,
-            &mut transparents,
-            &mut transforms,
-            &mut global_transforms,
-            &mut sprites,
-            &mut start_pieces,
-            &mut middle_pieces,
-            &mut end_pieces,
-            &mut backgrounds,
-            &mut foregrounds,
-            &anims,
-            entity,
-            key,
-        );
-
-        vec![(false, left), (true, right)]
-            .iter()
-            .for_each(|(i, v)| {
-                self.create_alternative_key(
-                    entities,
-                    &mut parents,
-                    &mut transparents,
-                    &mut transforms,
-                    &mut global_transforms,
-                    &mut sprites,
-                    &mut alternative_keys,
-                    anims,
-                    entity,
-                    v,
-                    *i,
-                )
-            });
-
-        entity
-    }
-
-    fn create_bar(
-        &self,
-        entities: &Entities<'s>,
-        mut parents: &mut WriteStorage<'s, Parent>,
-        mut hiddens: &mut WriteStorage<'s, Hidden>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
-        mut transforms: &mut WriteStorage<'s, Transform>,
-        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut start_pieces: &mut WriteStorage<'s, StartBarPiece>,
-        mut middle_pieces: &mut WriteStorage<'s, MiddleBarPiece>,
-        mut end_pieces: &mut WriteStorage<'s, EndBarPiece>,
-        mut backgrounds: &mut WriteStorage<'s, BarBackground>,
-        mut foregrounds: &mut WriteStorage<'s, BarForeground>,
-        anims: &Read<'s, Animations>,
-        entity: Entity,
-        key: String,
-    ) {
-        let mut prompt_transform = Transform::default();
-        prompt_transform.translation.z = 12.0;
-
-        let anim = &anims.animations["white_progress"];
-        let _bar = entities
-            .build_entity()
-            .with(
-                SpriteRender {
-                    sprite_sheet: anim.obtain_handle(),
-                    sprite_number: anim.get_frame_at(0.0, false),
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                },
-                &mut sprites,
-            )
-            .with(Parent { entity }, &mut parents)
-            .with(Transparent, &mut transparents)
-            .with(BarForeground, &mut foregrounds)
-            .with(prompt_transform, &mut transforms)
-            .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
-    }
-
-    fn create_hold_key(
-        &self,
-        entities: &Entities<'s>,
-        mut parents: &mut WriteStorage<'s, Parent>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
-        mut transforms: &mut WriteStorage<'s, Transform>,
-        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut hold_keys: &mut WriteStorage<'s, HoldKey>,
-        anims: &Read<'s, Animations>,
-        entity: Entity,
-        key: String,
-    ) {
-        let mut prompt_transform = Transform::default();
-        prompt_transform.translation.y = 12.0;
-        prompt_transform.translation.z = 12.0;
-
-        let anim = &anims.animations[&format!("prompt_{}", key)];
-        let _button = entities
-            .build_entity()
-            .with(
-                SpriteRender {
-                    sprite_sheet: anim.obtain_handle(),
-                    sprite_number: anim.get_frame(),
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                },
-                &mut sprites,
-            )
-            .with(Parent { entity }, &mut parents)
-            .with(Transparent, &mut transparents)
-            .with(HoldKey, &mut hold_keys)
-            .with(prompt_transform, &mut transforms)
-            .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
-    }
-
-    fn create_sequence_key(
-        &self,
-        entities: &Entities<'s>,
-        mut parents: &mut WriteStorage<'s, Parent>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
-        mut transforms: &mut WriteStorage<'s, Transform>,
-        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut sequence_keys: &mut WriteStorage<'s, SequenceKey>,
-        anims: &Read<'s, Animations>,
-        entity: Entity,
-        key: &String,
-        index: usize,
-    ) {
-        let mut prompt_transform = Transform::default();
-        prompt_transform.translation.x = ((index as isize - 2) as f32 + 0.5) * 20.0;
-        prompt_transform.translation.y = 12.0;
-        prompt_transform.translation.z = 12.0;
-
-        let anim = &anims.animations[&format!("prompt_{}", key)];
-        let _button = entities
+        entities
             .build_entity()
             .with(
-                SpriteRender {
-                    sprite_sheet: anim.obtain_handle(),
-                    sprite_number: anim.get_frame(),
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                },
-                &mut sprites,
+                ToppingInteraction::new(topping, rng),
+                &mut topping_interactions,
             )
-            .with(Parent { entity }, &mut parents)
-            .with(Transparent, &mut transparents)
-            .with(SequenceKey(index), &mut sequence_keys)
-            .with(prompt_transform, &mut transforms)
+            .with(Parent { entity: player }, &mut parents)
+            .with(Self::interaction_transform(), &mut transforms)
             .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
+            .build()
     }
 
-    fn create_alternative_key(
-        &self,
-        entities: &Entities<'s>,
-        mut parents: &mut WriteStorage<'s, Parent>,
-        mut transparents: &mut WriteStorage<'s, Transparent>,
-        mut transforms: &mut WriteStorage<'s, Transform>,
-        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
-        mut sprites: &mut WriteStorage<'s, SpriteRender>,
-        mut alternative_keys: &mut WriteStorage<'s, AlternativeKey>,
-        anims: &Read<'s, Animations>,
-        entity: Entity,
-        key: &String,
-        side: bool,
-    ) {
-        let mut prompt_transform = Transform::default();
-        prompt_transform.translation.x = if side { -10.0 } else { 10.0 };
-        prompt_transform.translation.y = 12.0;
-        prompt_transform.translation.z = 12.0;
-
-        let anim = &anims.animations[&format!("prompt_{}", key)];
-        let _button = entities
-            .build_entity()
-            .with(
-                SpriteRender {
-                    sprite_sheet: anim.obtain_handle(),
-                    sprite_number: anim.get_frame(),
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                },
-                &mut sprites,
-            )
-            .with(Parent { entity }, &mut parents)
-            .with(Transparent, &mut transparents)
-            .with(AlternativeKey(side), &mut alternative_keys)
-            .with(prompt_transform, &mut transforms)
-            .with(GlobalTransform::default(), &mut global_transforms)
-            .build();
+    /// Bar and prompts are attached to this entity by the `InteractionRenderSystem`.
+    fn interaction_transform() -> Transform {
+        let mut transform = Transform::default();
+        transform.translation.y = 24.0;
+        transform.scale.x = 0.5;
+        transform.scale.y = 0.5;
+        transform
     }
 }
@@ -1,12 +1,9 @@
 use amethyst::{
     assets::AssetStorage,
     audio::{output::Output, Source},
-    core::{
-        timing::Time,
-        transform::{ParentHierarchy, Transform},
-    },
-    ecs::prelude::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, WriteStorage},
-    renderer::{Hidden, SpriteRender},
+    core::transform::{ParentHierarchy, Transform},
+    ecs::prelude::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage},
+    shrev::EventChannel,
 };
 use crate::component::*;
 use either::*;
@@ -31,21 +28,11 @@ pub struct InteractionSystemData<'s> {
     preparation_interactions: WriteStorage<'s, PreparationInteraction>,
     topping_interactions: WriteStorage<'s, ToppingInteraction>,
     tables: WriteStorage<'s, Table>,
-    sprites: WriteStorage<'s, SpriteRender>,
-    hiddens: WriteStorage<'s, Hidden>,
-    start_pieces: WriteStorage<'s, StartBarPiece>,
-    middle_pieces: WriteStorage<'s, MiddleBarPiece>,
-    end_pieces: WriteStorage<'s, EndBarPiece>,
-    backgrounds: WriteStorage<'s, BarBackground>,
-    foregrounds: WriteStorage<'s, BarForeground>,
-    hold_keys: WriteStorage<'s, HoldKey>,
-    sequence_keys: WriteStorage<'s, SequenceKey>,
-    alternative_keys: WriteStorage<'s, AlternativeKey>,
-    anims: Read<'s, Animations>,
+    order_events: Write<'s, EventChannel<OrderEvent>>,
     storage: Read<'s, AssetStorage<Source>>,
-    sounds: ReadExpect<'s, Sounds>,
+    sounds: Option<Read<'s, Sounds>>,
     audio_output: Option<Read<'s, Output>>,
-    time: Read<'s, Time>,
+    tick: Read<'s, Tick>,
 }
 
 impl<'s> System<'s> for InteractionSystem {
@@ -66,24 +53,15 @@ impl<'s> System<'s> for InteractionSystem {
             mut preparation_interactions,
             mut topping_interactions,
             mut tables,
-            mut sprites,
-            mut hiddens,
-            mut start_pieces,
-            mut middle_pieces,
-            mut end_pieces,
-            mut backgrounds,
-            mut foregrounds,
-            mut hold_keys,
-            mut sequence_keys,
-            mut alternative_keys,
-            anims,
+            mut order_events,
             storage,
             sounds,
             audio_output,
-            time,
+            tick,
         }: Self::SystemData,
     ) {
-        for (mut player, mut input) in (&mut players, &mut inputs).join() {
+        for (player_entity, mut player, mut input) in (&*entities, &mut players, &mut inputs).join()
+        {
             if let Some(e) = player.interaction {
                 if let Some(ref mut fi) = flavor_interactions.get_mut(e) {
                     // Player has interaction and it is flavor.
@@ -93,7 +71,7 @@ impl<'s> System<'s> for InteractionSystem {
                         InteractionKey::West => input.wants_west,
                         InteractionKey::East => input.wants_east,
                     } {
-                        fi.add_progress(time.delta_seconds());
+                        fi.add_progress(tick.delta_seconds());
                         if fi.is_complete() {
                             // Reset button inputs
                             input.wants_north = false;
@@ -102,6 +80,11 @@ impl<'s> System<'s> for InteractionSystem {
                             input.wants_east = false;
                             // Insert flavor into player inventory
                             player.inventory = Some(Either::Left(fi.flavor.clone()));
+                            order_events.single_write(OrderEvent::Scoop {
+                                player: player_entity,
+                                team: player.team_index,
+                                flavor: fi.flavor.clone(),
+                            });
                             // Delete interaction entity
                             player.interaction = None;
                             for child in parent_hierarchy.children(e) {
@@ -109,24 +92,17 @@ impl<'s> System<'s> for InteractionSystem {
                             }
                             entities.delete(e).unwrap();
                             // Play sound
-                            play_pickup(
-                                &*sounds,
-                                &storage,
-                                audio_output.as_ref().map(|o| o.deref()),
-                            );
+                            if let Some(sounds) = &sounds {
+                                play_pickup(
+                                    &*sounds,
+                                    &storage,
+                                    audio_output.as_ref().map(|o| o.deref()),
+                                );
+                            }
                             continue;
                         }
                     } else {
-                        fi.remove_progress(time.delta_seconds());
-                    }
-                    for child in parent_hierarchy.children(e) {
-                        if !self.update_child(
-                            child,
-                            &anims,
-                            &mut foregrounds,
-                            &mut sprites,
-                            fi.progress,
-                        ) {};
+                        fi.remove_progress(tick.delta_seconds());
                     }
                 } else if let Some(ref mut pi) = preparation_interactions.get_mut(e) {
                     // Player has interaction and it is preparation.
@@ -152,6 +128,10 @@ impl<'s> System<'s> for InteractionSystem {
                             order.completed = true;
                             player.inventory = Some(Either::Right(order));
                             player.interaction = None;
+                            order_events.single_write(OrderEvent::Pickup {
+                                player: player_entity,
+                                team: player.team_index,
+                            });
                             for child in parent_hierarchy.children(e) {
                                 entities.delete(*child).unwrap();
                             }
@@ -159,28 +139,9 @@ impl<'s> System<'s> for InteractionSystem {
                             continue;
                         }
                     }
-                    for child in parent_hierarchy.children(e) {
-                        if !self.update_child(
-                            child,
-                            &anims,
-                            &mut foregrounds,
-                            &mut sprites,
-                            pi.progress,
-                        ) {
-                            if let Some(SequenceKey(index)) = sequence_keys.get(*child) {
-                                let anim = &anims.animations[&format!(
-                                    "prompt_{}",
-                                    pi.current_key(*index, &player.gamepad_style)
-                                )];
-                                let sprite = sprites.get_mut(*child).unwrap();
-                                sprite.sprite_sheet = anim.obtain_handle();
-                                sprite.sprite_number = anim.get_frame();
-                            }
-                        };
-                    }
                 } else if let Some(ref mut ti) = topping_interactions.get_mut(e) {
                     // Player has interaction and it is toppings.
-                    ti.remove_progress(time.delta_seconds());
+                    ti.remove_progress(tick.delta_seconds());
                     if let Some(key) = if input.wants_north {
                         Some(InteractionKey::North)
                     } else if input.wants_south {
@@ -212,6 +173,11 @@ impl<'s> System<'s> for InteractionSystem {
                                 .unwrap()
                                 .insert_topping(ti.topping.clone());
                             player.interaction = None;
+                            order_events.single_write(OrderEvent::Topping {
+                                player: player_entity,
+                                team: player.team_index,
+                                topping: ti.topping.clone(),
+                            });
                             for child in parent_hierarchy.children(e) {
                                 entities.delete(*child).unwrap();
                             }
@@ -219,15 +185,6 @@ impl<'s> System<'s> for InteractionSystem {
                             continue;
                         }
                     }
-                    for child in parent_hierarchy.children(e) {
-                        if !self.update_child(
-                            child,
-                            &anims,
-                            &mut foregrounds,
-                            &mut sprites,
-                            ti.progress,
-                        ) {}
-                    }
                 } else {
                     error!("SOMETHING IS WRONG, INTERACTION OF NO TYPE");
                 }
@@ -235,23 +192,3 @@ impl<'s> System<'s> for InteractionSystem {
         }
     }
 }
-
-impl<'s> InteractionSystem {
-    fn update_child(
-        &self,
-        child: &'s Entity,
-        anims: &Read<'s, Animations>,
-        foregrounds: &mut WriteStorage<'s, BarForeground>,
-        sprites: &mut WriteStorage<'s, SpriteRender>,
-        progress: f32,
-    ) -> bool {
-        if let Some(_) = foregrounds.get(*child) {
-            let sprite = sprites.get_mut(*child).unwrap();
-            let anim = &anims.animations["white_progress"];
-            sprite.sprite_number = anim.get_frame_at(progress, false);
-            true
-        } else {
-            false
-        }
-    }
-}
@@ -0,0 +1,317 @@
+use amethyst::{
+    core::transform::{GlobalTransform, Parent, ParentHierarchy, Transform},
+    ecs::prelude::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, WriteStorage},
+    renderer::{SpriteRender, Transparent},
+};
+use crate::component::*;
+use nk_data::*;
+use shred_derive::*;
+
+/// Spawns and updates the progress bar and key prompts of every running interaction. Gameplay
+/// systems only create the bare interaction entity, so this is the only part needing sprites.
+pub struct InteractionRenderSystem;
+
+#[derive(SystemData)]
+pub struct InteractionRenderSystemData<'s> {
+    parent_hierarchy: ReadExpect<'s, ParentHierarchy>,
+    entities: Entities<'s>,
+    players: ReadStorage<'s, Player>,
+    flavor_interactions: ReadStorage<'s, FlavorInteraction>,
+    preparation_interactions: ReadStorage<'s, PreparationInteraction>,
+    topping_interactions: ReadStorage<'s, ToppingInteraction>,
+    visuals: WriteStorage<'s, InteractionVisuals>,
+    parents: WriteStorage<'s, Parent>,
+    transforms: WriteStorage<'s, Transform>,
+    global_transforms: WriteStorage<'s, GlobalTransform>,
+    sprites: WriteStorage<'s, SpriteRender>,
+    transparents: WriteStorage<'s, Transparent>,
+    foregrounds: WriteStorage<'s, BarForeground>,
+    hold_keys: WriteStorage<'s, HoldKey>,
+    sequence_keys: WriteStorage<'s, SequenceKey>,
+    alternative_keys: WriteStorage<'s, AlternativeKey>,
+    anims: Read<'s, Animations>,
+}
+
+impl<'s> System<'s> for InteractionRenderSystem {
+    type SystemData = InteractionRenderSystemData<'s>;
+
+    fn run(
+        &mut self,
+        InteractionRenderSystemData {
+            parent_hierarchy,
+            entities,
+            players,
+            flavor_interactions,
+            preparation_interactions,
+            topping_interactions,
+            mut visuals,
+            mut parents,
+            mut transforms,
+            mut global_transforms,
+            mut sprites,
+            mut transparents,
+            mut foregrounds,
+            mut hold_keys,
+            mut sequence_keys,
+            mut alternative_keys,
+            anims,
+        }: Self::SystemData,
+    ) {
+        let interactions = (&*entities, &parents)
+            .join()
+            .filter(|(e, parent)| {
+                players
+                    .get(parent.entity)
+                    .map(|p| p.interaction == Some(*e))
+                    .unwrap_or(false)
+            })
+            .map(|(e, parent)| (e, parent.entity))
+            .collect::<Vec<_>>();
+
+        for (e, player_entity) in interactions {
+            let style = &players.get(player_entity).unwrap().gamepad_style;
+
+            if let None = visuals.get(e) {
+                self.create_bar(
+                    &entities,
+                    &mut parents,
+                    &mut transparents,
+                    &mut transforms,
+                    &mut global_transforms,
+                    &mut sprites,
+                    &mut foregrounds,
+                    &anims,
+                    e,
+                );
+
+                if let Some(fi) = flavor_interactions.get(e) {
+                    self.create_hold_key(
+                        &entities,
+                        &mut parents,
+                        &mut transparents,
+                        &mut transforms,
+                        &mut global_transforms,
+                        &mut sprites,
+                        &mut hold_keys,
+                        &anims,
+                        e,
+                        fi.key.get_str(style),
+                    );
+                } else if let Some(pi) = preparation_interactions.get(e) {
+                    (0..4).for_each(|i| {
+                        self.create_sequence_key(
+                            &entities,
+                            &mut parents,
+                            &mut transparents,
+                            &mut transforms,
+                            &mut global_transforms,
+                            &mut sprites,
+                            &mut sequence_keys,
+                            &anims,
+                            e,
+                            &pi.current_key(i, style),
+                            i,
+                        )
+                    });
+                } else if let Some(ti) = topping_interactions.get(e) {
+                    vec![false, true].into_iter().for_each(|side| {
+                        self.create_alternative_key(
+                            &entities,
+                            &mut parents,
+                            &mut transparents,
+                            &mut transforms,
+                            &mut global_transforms,
+                            &mut sprites,
+                            &mut alternative_keys,
+                            &anims,
+                            e,
+                            &ti.current_key(side, style),
+                            side,
+                        )
+                    });
+                }
+
+                visuals.insert(e, InteractionVisuals).unwrap();
+                continue;
+            }
+
+            let progress = if let Some(fi) = flavor_interactions.get(e) {
+                fi.progress
+            } else if let Some(pi) = preparation_interactions.get(e) {
+                pi.progress
+            } else if let Some(ti) = topping_interactions.get(e) {
+                ti.progress
+            } else {
+                continue;
+            };
+
+            for child in parent_hierarchy.children(e) {
+                if let Some(_) = foregrounds.get(*child) {
+                    let sprite = sprites.get_mut(*child).unwrap();
+                    let anim = &anims.animations["white_progress"];
+                    sprite.sprite_number = anim.get_frame_at(progress, false);
+                } else if let (Some(SequenceKey(index)), Some(pi)) =
+                    (sequence_keys.get(*child), preparation_interactions.get(e))
+                {
+                    let anim =
+                        &anims.animations[&format!("prompt_{}", pi.current_key(*index, style))];
+                    let sprite = sprites.get_mut(*child).unwrap();
+                    sprite.sprite_sheet = anim.obtain_handle();
+                    sprite.sprite_number = anim.get_frame();
+                }
+            }
+        }
+    }
+}
+
+impl<'s> InteractionRenderSystem {
+    fn create_bar(
+        &self,
+        entities: &Entities<'s>,
+        mut parents: &mut WriteStorage<'s, Parent>,
+        mut transparents: &mut WriteStorage<'s, Transparent>,
+        mut transforms: &mut WriteStorage<'s, Transform>,
+        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
+        mut sprites: &mut WriteStorage<'s, SpriteRender>,
+        mut foregrounds: &mut WriteStorage<'s, BarForeground>,
+        anims: &Read<'s, Animations>,
+        entity: Entity,
+    ) {
+        let mut prompt_transform = Transform::default();
+        prompt_transform.translation.z = 12.0;
+
+        let anim = &anims.animations["white_progress"];
+        let _bar = entities
+            .build_entity()
+            .with(
+                SpriteRender {
+                    sprite_sheet: anim.obtain_handle(),
+                    sprite_number: anim.get_frame_at(0.0, false),
+                    flip_horizontal: false,
+                    flip_vertical: false,
+                },
+                &mut sprites,
+            )
+            .with(Parent { entity }, &mut parents)
+            .with(Transparent, &mut transparents)
+            .with(BarForeground, &mut foregrounds)
+            .with(prompt_transform, &mut transforms)
+            .with(GlobalTransform::default(), &mut global_transforms)
+            .build();
+    }
+
+    fn create_hold_key(
+        &self,
+        entities: &Entities<'s>,
+        mut parents: &mut WriteStorage<'s, Parent>,
+        mut transparents: &mut WriteStorage<'s, Transparent>,
+        mut transforms: &mut WriteStorage<'s, Transform>,
+        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
+        mut sprites: &mut WriteStorage<'s, SpriteRender>,
+        mut hold_keys: &mut WriteStorage<'s, HoldKey>,
+        anims: &Read<'s, Animations>,
+        entity: Entity,
+        key: String,
+    ) {
+        let mut prompt_transform = Transform::default();
+        prompt_transform.translation.y = 12.0;
+        prompt_transform.translation.z = 12.0;
+
+        let anim = &anims.animations[&format!("prompt_{}", key)];
+        let _button = entities
+            .build_entity()
+            .with(
+                SpriteRender {
+                    sprite_sheet: anim.obtain_handle(),
+                    sprite_number: anim.get_frame(),
+                    flip_horizontal: false,
+                    flip_vertical: false,
+                },
+                &mut sprites,
+            )
+            .with(Parent { entity }, &mut parents)
+            .with(Transparent, &mut transparents)
+            .with(HoldKey, &mut hold_keys)
+            .with(prompt_transform, &mut transforms)
+            .with(GlobalTransform::default(), &mut global_transforms)
+            .build();
+    }
+
+    fn create_sequence_key(
+        &self,
+        entities: &Entities<'s>,
+        mut parents: &mut WriteStorage<'s, Parent>,
+        mut transparents: &mut WriteStorage<'s, Transparent>,
+        mut transforms: &mut WriteStorage<'s, Transform>,
+        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
+        mut sprites: &mut WriteStorage<'s, SpriteRender>,
+        mut sequence_keys: &mut WriteStorage<'s, SequenceKey>,
+        anims: &Read<'s, Animations>,
+        entity: Entity,
+        key: &String,
+        index: usize,
+    ) {
+        let mut prompt_transform = Transform::default();
+        prompt_transform.translation.x = ((index as isize - 2) as f32 + 0.5) * 20.0;
+        prompt_transform.translation.y = 12.0;
+        prompt_transform.translation.z = 12.0;
+
+        let anim = &anims.animations[&format!("prompt_{}", key)];
+        let _button = entities
+            .build_entity()
+            .with(
+                SpriteRender {
+                    sprite_sheet: anim.obtain_handle(),
+                    sprite_number: anim.get_frame(),
+                    flip_horizontal: false,
+                    flip_vertical: false,
+                },
+                &mut sprites,
+            )
+            .with(Parent { entity }, &mut parents)
+            .with(Transparent, &mut transparents)
+            .with(SequenceKey(index), &mut sequence_keys)
+            .with(prompt_transform, &mut transforms)
+            .with(GlobalTransform::default(), &mut global_transforms)
+            .build();
+    }
+
+    fn create_alternative_key(
+        &self,
+        entities: &Entities<'s>,
+        mut parents: &mut WriteStorage<'s, Parent>,
+        mut transparents: &mut WriteStorage<'s, Transparent>,
+        mut transforms: &mut WriteStorage<'s, Transform>,
+        mut global_transforms: &mut WriteStorage<'s, GlobalTransform>,
+        mut sprites: &mut WriteStorage<'s, SpriteRender>,
+        mut alternative_keys: &mut WriteStorage<'s, AlternativeKey>,
+        anims: &Read<'s, Animations>,
+        entity: Entity,
+        key: &String,
+        side: bool,
+    ) {
+        let mut prompt_transform = Transform::default();
+        prompt_transform.translation.x = if side { -10.0 } else { 10.0 };
+        prompt_transform.translation.y = 12.0;
+        prompt_transform.translation.z = 12.0;
+
+        let anim = &anims.animations[&format!("prompt_{}", key)];
+        let _button = entities
+            .build_entity()
+            .with(
+                SpriteRender {
+                    sprite_sheet: anim.obtain_handle(),
+                    sprite_number: anim.get_frame(),
+                    flip_horizontal: false,
+                    flip_vertical: false,
+                },
+                &mut sprites,
+            )
+            .with(Parent { entity }, &mut parents)
+            .with(Transparent, &mut transparents)
+            .with(AlternativeKey(side), &mut alternative_keys)
+            .with(prompt_transform, &mut transforms)
+            .with(GlobalTransform::default(), &mut global_transforms)
+            .build();
+    }
+}
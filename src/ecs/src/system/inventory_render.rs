@@ -65,14 +65,10 @@ impl<'s> System<'s> for InventoryRenderSystem {
                                     if let Some(_) = hiddens.get(e) {
                                         hiddens.remove(e);
                                     }
-                                    let key = &defs
-                                        .flavors()
-                                        .find(|a| a.index == *f)
-                                        .expect(&format!(
-                                            "Flavor Definition must include index <{:?}>",
-                                            f
-                                        ))
-                                        .key;
+                                    let key = match defs.get_flavor(f) {
+                                        Some(flavor) => &flavor.key,
+                                        None => continue,
+                                    };
                                     let anim = &anims
                                         .get(&format!("{}_scoop", &key))
                                         .expect(&format!("{}_scoop not found", &key));
@@ -90,12 +86,12 @@ impl<'s> System<'s> for InventoryRenderSystem {
                                 }
                             },
                             Some(Either::Right(o)) => {
-                                let (handle, frame) = if inv.0 == 6 {
+                                let (handle, frame) = if inv.0 == 5 {
                                     let progress = o.melt_percent();
                                     let a = &anims.get("color_progress").unwrap();
                                     (a.obtain_handle(), a.get_frame_at(progress, false))
-                                } else if inv.0 == 5 {
-                                    let (first, second) = o.get_topping_key(&defs);
+                                } else if inv.0 >= 6 {
+                                    let (first, second) = o.get_topping_key(inv.0 - 6, &defs);
                                     if let Some(a) = anims.get(&first) {
                                         (a.obtain_handle(), a.get_frame())
                                     } else {
@@ -105,10 +101,7 @@ impl<'s> System<'s> for InventoryRenderSystem {
                                 } else {
                                     let key = &match inv.0 {
                                         0 => o.get_preparation_key(&defs),
-                                        1 => o.get_flavor_a_key(&defs),
-                                        2 => o.get_flavor_b_key(&defs),
-                                        3 => o.get_flavor_c_key(&defs),
-                                        4 => o.get_flavor_d_key(&defs),
+                                        1..=4 => o.get_flavor_key(inv.0 - 1, &defs),
                                         _ => std::unreachable!(),
                                     };
                                     let a = &anims.get(key).unwrap();
@@ -118,44 +111,14 @@ impl<'s> System<'s> for InventoryRenderSystem {
                                 sprite.sprite_number = frame;
 
                                 let flavor_count = o.flavor_count();
-                                let preparation_def = defs
-                                    .preparations()
-                                    .find(|x| x.index == o.preparation)
-                                    .unwrap();
-
                                 let (x, y) = match inv.0 {
                                     0 => (0.0, 0.0),
-                                    1 => match flavor_count {
-                                        1 => preparation_def.offsets.one[0],
-                                        2 => preparation_def.offsets.two[0],
-                                        3 => preparation_def.offsets.three[0],
-                                        4 => preparation_def.offsets.four[0],
-                                        _ => std::unreachable!(),
-                                    },
-                                    2 => match flavor_count {
-                                        1 => (0.0, 0.0),
-                                        2 => preparation_def.offsets.two[1],
-                                        3 => preparation_def.offsets.three[1],
-                                        4 => preparation_def.offsets.four[1],
-                                        _ => std::unreachable!(),
-                                    },
-                                    3 => match flavor_count {
-                                        1 => (0.0, 0.0),
-                                        2 => (0.0, 0.0),
-                                        3 => preparation_def.offsets.three[2],
-                                        4 => preparation_def.offsets.four[2],
-                                        _ => std::unreachable!(),
-                                    },
-                                    4 => match flavor_count {
-                                        1 => (0.0, 0.0),
-                                        2 => (0.0, 0.0),
-                                        3 => (0.0, 0.0),
-                                        4 => preparation_def.offsets.four[3],
-                                        _ => std::unreachable!(),
-                                    },
-                                    5 => (0.0, 0.0),
-                                    6 => (0.0, 12.0),
-                                    _ => std::unreachable!(),
+                                    1..=4 => defs
+                                        .get_preparation(&o.preparation)
+                                        .map(|p| p.flavor_offset(flavor_count, inv.0 - 1))
+                                        .unwrap_or((0.0, 0.0)),
+                                    5 => (0.0, 12.0),
+                                    _ => (0.0, 0.0),
                                 };
 
                                 transform.translation.x = x;
@@ -0,0 +1,103 @@
+use super::control::apply_axis;
+use amethyst::{core::cgmath::*, ecs::prelude::*, input::InputHandler};
+use crate::component::*;
+use nk_data::*;
+use std::collections::HashMap;
+
+/// Player whose bindings a keyboard half plays with, the full keyboard plays as player one.
+pub fn keyboard_prefix(style: Style) -> &'static str {
+    match style {
+        Style::Full | Style::HalfLeft => "player_one",
+        Style::HalfRight => "player_two",
+    }
+}
+
+/// Name of a keyboard binding in `resources/input.ron`, `player_one_horizontal_keyboard` and so
+/// on.
+pub fn keyboard_binding(style: Style, name: &str) -> String {
+    format!("{}_{}_keyboard", keyboard_prefix(style), name)
+}
+
+/// Whether `resources/input.ron` binds the movement and actions a keyboard half plays with.
+pub fn keyboard_bound(handler: &InputHandler<String, String>, style: Style) -> bool {
+    ["horizontal", "vertical"]
+        .iter()
+        .all(|a| handler.axis_value(&keyboard_binding(style, a)).is_some())
+        && ["interact", "special"].iter().all(|a| {
+            handler
+                .action_is_down(&keyboard_binding(style, a))
+                .is_some()
+        })
+}
+
+/// Whether the pause action of a keyboard half is held down, see `Game` and `Paused`.
+pub fn keyboard_pause_down(handler: &InputHandler<String, String>, style: Style) -> bool {
+    handler
+        .action_is_down(&keyboard_binding(style, "pause"))
+        .unwrap_or(false)
+}
+
+/// Feeds keyboard players from the `InputHandler` bindings in `resources/input.ron`. Each half of
+/// the keyboard moves with the `player_*_keyboard` axes and presses north, south, west and east
+/// with the `player_*_aim_keyboard` axes, like a gamepad's face buttons.
+#[derive(Default)]
+pub struct KeyboardSystem {
+    held: HashMap<String, bool>,
+}
+
+impl<'s> System<'s> for KeyboardSystem {
+    type SystemData = (
+        Read<'s, InputHandler<String, String>>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Input>,
+    );
+
+    fn run(&mut self, (handler, players, mut inputs): Self::SystemData) {
+        for (player, input) in (&players, &mut inputs).join() {
+            if player.device != InputDevice::Keyboard {
+                continue;
+            }
+            let style = player.gamepad_style;
+            let axis = |name: &str| {
+                handler
+                    .axis_value(&keyboard_binding(style, name))
+                    .unwrap_or(0.0) as f32
+            };
+
+            if let None = player.interaction {
+                apply_axis(
+                    input,
+                    Vector2::new(axis("horizontal"), axis("vertical")),
+                    player.invert_x_axis,
+                );
+            } else {
+                input.wants_to_move = None;
+            }
+
+            // Like gamepad buttons, actions are set when pressed and cleared when released.
+            let held = &mut self.held;
+            let mut press = |name: String, down: bool, wants: &mut bool| {
+                let was = held.insert(name, down).unwrap_or(false);
+                if down != was {
+                    *wants = down;
+                }
+            };
+            let action = |name: &str| {
+                let name = keyboard_binding(style, name);
+                let down = handler.action_is_down(&name).unwrap_or(false);
+                (name, down)
+            };
+            let (name, down) = action("interact");
+            press(name, down, &mut input.wants_to_interact);
+            let (name, down) = action("special");
+            press(name, down, &mut input.wants_special);
+
+            let (aim_x, aim_y) = (axis("horizontal_aim"), axis("vertical_aim"));
+            let aim = |direction: &str| keyboard_binding(style, &format!("aim_{}", direction));
+            press(aim("north"), aim_y > 0.0, &mut input.wants_north);
+            press(aim("south"), aim_y < 0.0, &mut input.wants_south);
+            press(aim("west"), aim_x < 0.0, &mut input.wants_west);
+            press(aim("east"), aim_x > 0.0, &mut input.wants_east);
+        }
+    }
+}
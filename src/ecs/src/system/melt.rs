@@ -1,22 +1,41 @@
 use amethyst::{
-    core::timing::Time,
-    ecs::prelude::{Entities, Join, Read, System, WriteStorage},
+    ecs::prelude::{Entities, Join, Read, System, Write, WriteStorage},
+    shrev::EventChannel,
 };
 use crate::component::*;
 use either::*;
+use nk_data::*;
 
 pub struct MeltSystem;
 
 impl<'s> System<'s> for MeltSystem {
-    type SystemData = (Entities<'s>, WriteStorage<'s, Player>, Read<'s, Time>);
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Player>,
+        Read<'s, Match>,
+        Read<'s, Tick>,
+        Write<'s, EventChannel<OrderEvent>>,
+    );
 
-    fn run(&mut self, (entities, mut players, time): Self::SystemData) {
-        let ds = time.delta_seconds();
-        for (_e, player) in (&*entities, &mut players).join() {
+    fn run(
+        &mut self,
+        (entities, mut players, match_data, tick, mut order_events): Self::SystemData,
+    ) {
+        let ds = tick.delta_seconds();
+        for (e, player) in (&*entities, &mut players).join() {
+            let melt_speed = match_data
+                .teams
+                .get(player.team_index)
+                .map(|t| t.melt_speed())
+                .unwrap_or(1.0);
             if let Some(Either::Right(o)) = &mut player.inventory {
-                o.update_delivery(ds);
+                o.update_delivery(ds * melt_speed);
                 if o.has_melted() {
                     player.inventory = None;
+                    order_events.single_write(OrderEvent::Melted {
+                        player: e,
+                        team: player.team_index,
+                    });
                 }
             }
         }
@@ -1,11 +1,12 @@
 use amethyst::{
-    core::{timing::Time, transform::Transform},
+    core::transform::Transform,
     ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage},
 };
 use crate::component::*;
 use log::*;
 use nalgebra::{Isometry2, Vector2};
 use ncollide2d::query::{self, *};
+use nk_data::*;
 
 pub struct MovementSystem;
 
@@ -17,14 +18,14 @@ impl<'s> System<'s> for MovementSystem {
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Velocity>,
         WriteStorage<'s, Direction>,
-        Read<'s, Time>,
+        Read<'s, Tick>,
     );
 
     fn run(
         &mut self,
-        (entities, mut transforms, mut velocities, mut directions, time): Self::SystemData,
+        (entities, mut transforms, mut velocities, mut directions, tick): Self::SystemData,
     ) {
-        let ds = time.delta_seconds();
+        let ds = tick.delta_seconds();
         for (e, transform, velocity) in (&*entities, &mut transforms, &mut velocities).join() {
             transform.translation.x += velocity.current.x * ds;
             transform.translation.y += velocity.current.y * ds;
@@ -0,0 +1,42 @@
+use amethyst::{
+    ecs::prelude::{Read, System, Write},
+    shrev::EventChannel,
+};
+use log::*;
+use nk_data::*;
+
+/// Counts down the time each team has to fulfil its requested orders and drops the ones that
+/// run out, taking the generation profile's `expiry_penalty` from the team.
+pub struct OrderTimerSystem;
+
+impl<'s> System<'s> for OrderTimerSystem {
+    type SystemData = (
+        Write<'s, Match>,
+        Read<'s, Tick>,
+        Write<'s, EventChannel<OrderEvent>>,
+    );
+
+    fn run(&mut self, (mut match_data, tick, mut order_events): Self::SystemData) {
+        let ds = tick.delta_seconds();
+        let penalty = match_data.generation.expiry_penalty;
+        for (index, team) in match_data.teams.iter_mut().enumerate() {
+            for order in team.orders.iter_mut() {
+                order.timer -= ds;
+            }
+            let expired = team.orders.iter().filter(|o| o.has_expired()).count();
+            if expired == 0 {
+                continue;
+            }
+            team.orders.retain(|o| !o.has_expired());
+            for _ in 0..expired {
+                let lost = penalty.min(team.score).max(0);
+                team.score -= lost;
+                info!("team {} let an order expire, lost {}", index, lost);
+                order_events.single_write(OrderEvent::Expired {
+                    team: index,
+                    penalty: lost,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use amethyst::ecs::prelude::{Join, Read, ReadStorage, System, Write, WriteStorage};
+use crate::component::*;
+use log::*;
+use nk_data::*;
+
+pub struct ReplayRecordSystem;
+
+impl<'s> System<'s> for ReplayRecordSystem {
+    type SystemData = (
+        Write<'s, ReplayMode>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Input>,
+        Read<'s, Tick>,
+    );
+
+    fn run(&mut self, (mut mode, players, inputs, tick): Self::SystemData) {
+        if let ReplayMode::Recording { ref mut replay, .. } = *mode {
+            let mut recorded = (&players, &inputs)
+                .join()
+                .map(|(p, i)| (p.palette_key.clone(), i.record()))
+                .collect::<Vec<_>>();
+            recorded.sort_by(|a, b| a.0.cmp(&b.0));
+            replay.frames.push(ReplayFrame {
+                delta: tick.delta_seconds(),
+                inputs: recorded,
+            });
+        }
+    }
+}
+
+/// Overwrites live inputs and the frame delta with the recorded ones.
+pub struct ReplayPlaybackSystem;
+
+impl<'s> System<'s> for ReplayPlaybackSystem {
+    type SystemData = (
+        Write<'s, ReplayMode>,
+        ReadStorage<'s, Player>,
+        WriteStorage<'s, Input>,
+        Write<'s, Tick>,
+    );
+
+    fn run(&mut self, (mut mode, players, mut inputs, mut tick): Self::SystemData) {
+        if let ReplayMode::Playing {
+            ref replay,
+            ref mut frame,
+        } = *mode
+        {
+            if players.join().next().is_none() {
+                // Match has not started yet.
+                return;
+            }
+            match replay.frames.get(*frame) {
+                Some(recorded) => {
+                    tick.set_delta_seconds(recorded.delta);
+                    for (player, input) in (&players, &mut inputs).join() {
+                        match recorded
+                            .inputs
+                            .iter()
+                            .find(|(k, _)| *k == player.palette_key)
+                        {
+                            Some((_, i)) => input.apply(i),
+                            None => input.apply(&RecordedInput::default()),
+                        }
+                    }
+                    *frame += 1;
+                    if *frame == replay.frames.len() {
+                        info!("REPLAY FINISHED AFTER {} FRAMES", frame);
+                    }
+                }
+                None => {
+                    for input in (&mut inputs).join() {
+                        input.apply(&RecordedInput::default());
+                    }
+                }
+            }
+        }
+    }
+}
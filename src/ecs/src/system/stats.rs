@@ -0,0 +1,89 @@
+use amethyst::{
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use crate::component::*;
+use nk_data::*;
+
+/// Tallies what every player did during the match and how each team's score evolved, for the
+/// results screen and the match report.
+pub struct StatsSystem {
+    reader: Option<ReaderId<OrderEvent>>,
+}
+
+impl StatsSystem {
+    pub fn new() -> Self {
+        StatsSystem { reader: None }
+    }
+}
+
+impl<'s> System<'s> for StatsSystem {
+    type SystemData = (
+        Read<'s, EventChannel<OrderEvent>>,
+        Read<'s, Match>,
+        Read<'s, Tick>,
+        Write<'s, MatchStats>,
+        Entities<'s>,
+        ReadStorage<'s, Player>,
+    );
+
+    fn setup(&mut self, mut res: &mut Resources) {
+        Self::SystemData::setup(&mut res);
+        self.reader = Some(
+            res.fetch_mut::<EventChannel<OrderEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(&mut self, (channel, match_data, tick, mut stats, entities, players): Self::SystemData) {
+        while stats.teams.len() < match_data.teams.len() {
+            stats.teams.push(TeamStats {
+                score: vec![(match_data.elapsed, 0)],
+                expired: 0,
+            });
+        }
+
+        if let Some(ref mut reader) = &mut self.reader {
+            for event in channel.read(reader) {
+                match event {
+                    OrderEvent::Scoop { player, .. } => stats.player_mut(*player).scoops += 1,
+                    OrderEvent::Pickup { player, .. } => {
+                        stats.player_mut(*player).orders_prepared += 1
+                    }
+                    OrderEvent::Topping { player, .. } => stats.player_mut(*player).toppings += 1,
+                    OrderEvent::Delivery { player, score, .. } => {
+                        let entry = stats.player_mut(*player);
+                        entry.deliveries += 1;
+                        entry.points += score;
+                    }
+                    OrderEvent::PartialDelivery { player, score, .. } => {
+                        let entry = stats.player_mut(*player);
+                        entry.partial_deliveries += 1;
+                        entry.points += score;
+                    }
+                    OrderEvent::WrongDelivery { player, .. } => {
+                        stats.player_mut(*player).wrong_deliveries += 1
+                    }
+                    OrderEvent::Melted { player, .. } => stats.player_mut(*player).melted += 1,
+                    OrderEvent::Expired { team, .. } => {
+                        if let Some(team) = stats.teams.get_mut(*team) {
+                            team.expired += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (entity, player) in (&*entities, &players).join() {
+            if player.interaction.is_some() {
+                stats.player_mut(entity).interaction_time += tick.delta_seconds();
+            }
+        }
+
+        for (team, recorded) in match_data.teams.iter().zip(stats.teams.iter_mut()) {
+            if recorded.score.last().map(|(_, s)| *s) != Some(team.score) {
+                recorded.score.push((match_data.elapsed, team.score));
+            }
+        }
+    }
+}
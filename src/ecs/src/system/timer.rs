@@ -1,15 +1,16 @@
-use amethyst::{
-    core::timing::Time,
-    ecs::prelude::{Read, System, Write},
-};
+use amethyst::ecs::prelude::{Read, System, Write};
 use nk_data::*;
 
 pub struct TimerSystem;
 
 impl<'s> System<'s> for TimerSystem {
-    type SystemData = (Write<'s, Match>, Read<'s, Time>);
+    type SystemData = (Write<'s, Match>, Read<'s, Tick>);
 
-    fn run(&mut self, (mut match_data, time): Self::SystemData) {
-        match_data.timer -= time.delta_seconds();
+    fn run(&mut self, (mut match_data, tick): Self::SystemData) {
+        if match_data.is_over() {
+            return;
+        }
+        match_data.timer = (match_data.timer - tick.delta_seconds()).max(0.0);
+        match_data.elapsed += tick.delta_seconds();
     }
 }
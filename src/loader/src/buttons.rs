@@ -7,33 +7,28 @@ use amethyst::{
     },
     utils::application_root_dir,
 };
-use log::*;
+use crate::error::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::{collections::HashMap, fs::File};
+use std::collections::HashMap;
 
 pub fn load_interaction_texture(
     world: &mut World,
-) -> (
-    SpriteSheetHandle,
-    SpriteSheetHandle,
-    HashMap<String, Animation>,
-) {
+) -> Result<
+    (
+        SpriteSheetHandle,
+        SpriteSheetHandle,
+        HashMap<String, Animation>,
+    ),
+    LoadError,
+> {
     // Buttons
     let tindex = 49;
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/ui/buttons.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
     let (tex_def, anim_def): (
         TextureDefinition,
         HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-    ) = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing texture definition: {}", e);
-            panic!("Invalid texture definition <{}>!", path);
-        }
-    };
+    ) = load_ron(&path, "buttons texture")?;
     let texture = {
         let loader = world.read_resource::<Loader>();
         loader.load(
@@ -88,35 +83,30 @@ pub fn load_interaction_texture(
     let buttons_anim: HashMap<String, Animation> = anim_def
         .iter()
         .map(|(k, v)| {
-            (
+            Ok((
                 k.to_owned(),
                 Animation::new(
                     buttons_handle.clone(),
                     v.0.iter()
-                        .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                        .collect(),
+                        .map(|(name, duration)| {
+                            sprite_index(&sprites_hash, name, "buttons texture", &path)
+                                .map(|i| (i, *duration))
+                        })
+                        .collect::<Result<_, LoadError>>()?,
                     v.1.clone(),
                 ),
-            )
+            ))
         })
-        .into_iter()
-        .collect();
+        .collect::<Result<_, LoadError>>()?;
 
     // Progress
     let tindex = 48;
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/ui/timers.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
     let (tex_def, anim_def): (
         TextureDefinition,
         HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-    ) = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing texture definition: {}", e);
-            panic!("Invalid texture definition <{}>!", path);
-        }
-    };
+    ) = load_ron(&path, "timers texture")?;
     let texture = {
         let loader = world.read_resource::<Loader>();
         loader.load(
@@ -171,25 +161,27 @@ pub fn load_interaction_texture(
     let progress_anim: HashMap<String, Animation> = anim_def
         .iter()
         .map(|(k, v)| {
-            (
+            Ok((
                 k.to_owned(),
                 Animation::new(
                     progress_handle.clone(),
                     v.0.iter()
-                        .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                        .collect(),
+                        .map(|(name, duration)| {
+                            sprite_index(&sprites_hash, name, "timers texture", &path)
+                                .map(|i| (i, *duration))
+                        })
+                        .collect::<Result<_, LoadError>>()?,
                     v.1.clone(),
                 ),
-            )
+            ))
         })
-        .into_iter()
-        .collect();
+        .collect::<Result<_, LoadError>>()?;
 
-    (
+    Ok((
         buttons_handle,
         progress_handle,
         buttons_anim.into_iter().chain(progress_anim).collect(),
-    )
+    ))
 }
 /*
 *
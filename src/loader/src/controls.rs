@@ -0,0 +1,30 @@
+use amethyst::utils::application_root_dir;
+use crate::error::*;
+use log::*;
+use nk_data::*;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use std::path::Path;
+
+fn controls_path() -> String {
+    format!("{}/resources/controls.ron", application_root_dir())
+}
+
+/// A missing file means the controls were never changed and every player uses the defaults.
+pub fn load_controls() -> Result<ControlsConfig, LoadError> {
+    let path = controls_path();
+    if !Path::new(&path).exists() {
+        return Ok(ControlsConfig::default());
+    }
+    load_ron(&path, "controls config")
+}
+
+pub fn save_controls(controls: &ControlsConfig) {
+    let path = controls_path();
+    match to_string_pretty(controls, PrettyConfig::default()) {
+        Ok(s) => match std::fs::write(&path, s) {
+            Ok(_) => info!("Saved controls to <{}>.", path),
+            Err(e) => error!("Error writing controls <{}>: {}", path, e),
+        },
+        Err(e) => error!("Error serializing controls: {}", e),
+    }
+}
@@ -1,47 +1,96 @@
-use amethyst::{ecs::prelude::*, utils::application_root_dir};
-use log::*;
+use amethyst::utils::application_root_dir;
+use crate::error::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::fs::File;
 
-pub fn load_game_data() -> Definitions {
+fn keep<T>(result: Result<T, LoadError>, errors: &mut Vec<LoadError>) -> Option<T> {
+    result.map_err(|e| errors.push(e)).ok()
+}
+
+/// Preparations must have a place to draw every scoop of the orders they take.
+fn invalid_preparations(preparations: &[PreparationDef], path: &str) -> Vec<LoadError> {
+    preparations
+        .iter()
+        .flat_map(|p| {
+            p.validate()
+                .into_iter()
+                .map(move |error| LoadError::InvalidPreparation {
+                    path: path.to_owned(),
+                    key: p.key.clone(),
+                    error,
+                })
+        })
+        .collect()
+}
+
+/// Loads every data file even when some are broken, so all problems are reported at once.
+pub fn load_game_data() -> Result<Definitions, Vec<LoadError>> {
     let app_root = application_root_dir();
+    let path = |name: &str| format!("{}/assets/data/{}.ron", app_root, name);
 
-    let flavors = {
-        let path = format!("{}/assets/data/flavors.ron", app_root);
-        let f = File::open(&path).expect("Failed opening 'flavors.ron' file!");
-        match from_reader(f) {
-            Ok(x) => x,
-            Err(e) => {
-                error!("Error parsing flavors definition: {}", e);
-                panic!("Invalid flavors definition <{}>!", path);
-            }
-        }
-    };
+    let mut errors = Vec::new();
+    let flavors = keep(
+        load_ron(&path("flavors"), "flavors definition"),
+        &mut errors,
+    );
+    let preparations = keep(
+        load_ron(&path("preparations"), "preparations definition"),
+        &mut errors,
+    )
+    .filter(|preparations: &Vec<PreparationDef>| {
+        let invalid = invalid_preparations(preparations, &path("preparations"));
+        let valid = invalid.is_empty();
+        errors.extend(invalid);
+        valid
+    });
+    let toppings = keep(
+        load_ron(&path("toppings"), "toppings definition"),
+        &mut errors,
+    );
+    let hissatsu = keep(
+        load_ron(&path("hissatsu"), "hissatsu definition"),
+        &mut errors,
+    );
+    let houses = keep(load_ron(&path("houses"), "houses definition"), &mut errors);
+    let teams = keep(load_ron(&path("teams"), "teams definition"), &mut errors);
+    let players = keep(
+        load_ron(&path("players"), "players definition"),
+        &mut errors,
+    );
+    let generation = keep(
+        load_ron(&path("generation"), "generation definition"),
+        &mut errors,
+    );
 
-    let preparations = {
-        let path = format!("{}/assets/data/preparations.ron", app_root);
-        let f = File::open(&path).expect("Failed opening 'preparations.ron' file!");
-        match from_reader(f) {
-            Ok(x) => x,
-            Err(e) => {
-                error!("Error parsing preparations definition: {}", e);
-                panic!("Invalid preparations definition <{}>!", path);
-            }
-        }
-    };
+    // Files that failed to load are left empty so the duplicates of the others are still found.
+    let duplicates = Definitions::new(
+        flavors.unwrap_or_default(),
+        preparations.unwrap_or_default(),
+        toppings.unwrap_or_default(),
+    )
+    .with_hissatsu(hissatsu.unwrap_or_default())
+    .with_houses(houses.unwrap_or_default())
+    .with_teams(teams.unwrap_or_default())
+    .with_players(players.unwrap_or_default())
+    .with_generation(generation.unwrap_or_default())
+    .check();
 
-    let toppings = {
-        let path = format!("{}/assets/data/toppings.ron", app_root);
-        let f = File::open(&path).expect("Failed opening 'toppings.ron' file!");
-        match from_reader(f) {
-            Ok(x) => x,
-            Err(e) => {
-                error!("Error parsing toppings definition: {}", e);
-                panic!("Invalid toppings definition <{}>!", path);
-            }
+    match duplicates {
+        Ok(defs) if errors.is_empty() => Ok(defs),
+        Ok(_) => Err(errors),
+        Err(duplicates) => {
+            errors.extend(duplicates.into_iter().map(|error| LoadError::Duplicate {
+                // Definitions are named after their file, "flavors definition" and so on.
+                path: path(error.definition().trim_end_matches(" definition")),
+                error,
+            }));
+            Err(errors)
         }
-    };
+    }
+}
 
-    Definitions::new(flavors, preparations, toppings)
+/// Palette entries as `(key, a, r, g, b)`.
+pub fn load_palette() -> Result<Vec<(String, u8, u8, u8, u8)>, LoadError> {
+    let path = format!("{}/assets/data/palette.ron", application_root_dir());
+    let palette: [(String, u8, u8, u8, u8); 8] = load_ron(&path, "palette definition")?;
+    Ok(palette.to_vec())
 }
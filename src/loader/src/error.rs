@@ -0,0 +1,161 @@
+use nk_data::{DefinitionError, MapError, PreparationError, REPLAY_VERSION};
+use ron::de::from_reader;
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, error::Error, fmt, fs::File, io};
+
+/// Problem found while loading a data or texture file, `definition` names what was being loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    Open {
+        definition: &'static str,
+        path: String,
+        error: io::Error,
+    },
+    Parse {
+        definition: &'static str,
+        path: String,
+        position: Option<(usize, usize)>,
+        error: ron::de::Error,
+    },
+    MissingSprite {
+        definition: &'static str,
+        path: String,
+        sprite: String,
+    },
+    InvalidMap {
+        path: String,
+        error: MapError,
+    },
+    InvalidPreparation {
+        path: String,
+        key: String,
+        error: PreparationError,
+    },
+    Duplicate {
+        path: String,
+        error: DefinitionError,
+    },
+    ReplayVersion {
+        path: String,
+        version: u32,
+    },
+}
+
+impl LoadError {
+    pub fn path(&self) -> &str {
+        match self {
+            LoadError::Open { path, .. } => path,
+            LoadError::Parse { path, .. } => path,
+            LoadError::MissingSprite { path, .. } => path,
+            LoadError::InvalidMap { path, .. } => path,
+            LoadError::InvalidPreparation { path, .. } => path,
+            LoadError::Duplicate { path, .. } => path,
+            LoadError::ReplayVersion { path, .. } => path,
+        }
+    }
+
+    pub fn definition(&self) -> &'static str {
+        match self {
+            LoadError::Open { definition, .. } => definition,
+            LoadError::Parse { definition, .. } => definition,
+            LoadError::MissingSprite { definition, .. } => definition,
+            LoadError::InvalidMap { .. } => "map definition",
+            LoadError::InvalidPreparation { .. } => "preparations definition",
+            LoadError::Duplicate { error, .. } => error.definition(),
+            LoadError::ReplayVersion { .. } => "replay",
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Open {
+                definition,
+                path,
+                error,
+            } => write!(f, "failed opening {} <{}>: {}", definition, path, error),
+            LoadError::Parse {
+                definition,
+                path,
+                position: Some((line, col)),
+                error,
+            } => write!(
+                f,
+                "failed parsing {} <{}:{}:{}>: {}",
+                definition, path, line, col, error
+            ),
+            LoadError::Parse {
+                definition,
+                path,
+                position: None,
+                error,
+            } => write!(f, "failed parsing {} <{}>: {}", definition, path, error),
+            LoadError::MissingSprite {
+                definition,
+                path,
+                sprite,
+            } => write!(
+                f,
+                "{} <{}> animates unknown sprite '{}'",
+                definition, path, sprite
+            ),
+            LoadError::InvalidMap { path, error } => {
+                write!(f, "map definition <{}> cannot be played: {}", path, error)
+            }
+            LoadError::InvalidPreparation { path, key, error } => {
+                write!(f, "preparation '{}' in <{}> {}", key, path, error)
+            }
+            LoadError::Duplicate { path, error } => {
+                write!(
+                    f,
+                    "{} <{}> is ambiguous: {}",
+                    error.definition(),
+                    path,
+                    error
+                )
+            }
+            LoadError::ReplayVersion { path, version } => write!(
+                f,
+                "replay <{}> was recorded with version {} and this build plays version {}",
+                path, version, REPLAY_VERSION
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+/// Opens and deserializes a RON file, recording where it failed.
+pub fn load_ron<T: DeserializeOwned>(path: &str, definition: &'static str) -> Result<T, LoadError> {
+    let f = File::open(path).map_err(|error| LoadError::Open {
+        definition,
+        path: path.to_owned(),
+        error,
+    })?;
+    from_reader(f).map_err(|error| LoadError::Parse {
+        definition,
+        path: path.to_owned(),
+        position: match &error {
+            ron::de::Error::Parser(_, position) => Some((position.line, position.col)),
+            _ => None,
+        },
+        error,
+    })
+}
+
+pub(crate) fn sprite_index(
+    sprites: &HashMap<String, usize>,
+    name: &str,
+    definition: &'static str,
+    path: &str,
+) -> Result<usize, LoadError> {
+    sprites
+        .get(name)
+        .cloned()
+        .ok_or_else(|| LoadError::MissingSprite {
+            definition,
+            path: path.to_owned(),
+            sprite: name.to_owned(),
+        })
+}
@@ -7,25 +7,20 @@ use amethyst::{
     },
     utils::application_root_dir,
 };
+use crate::error::*;
 use log::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::{collections::HashMap, fs::File};
+use std::collections::HashMap;
 
-pub fn load_items_texture(world: &mut World) -> (SpriteSheetHandle, HashMap<String, Animation>) {
+pub fn load_items_texture(
+    world: &mut World,
+) -> Result<(SpriteSheetHandle, HashMap<String, Animation>), LoadError> {
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/item/old-index.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
     let (tex_def, anim_def): (
         TextureDefinition,
         HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-    ) = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing texture definition: {}", e);
-            panic!("Invalid texture definition <{}>!", path);
-        }
-    };
+    ) = load_ron(&path, "items texture")?;
 
     info!("Loading items texture.");
     // Load Textures
@@ -94,34 +89,29 @@ pub fn load_items_texture(world: &mut World) -> (SpriteSheetHandle, HashMap<Stri
     let anim_def = anim_def
         .iter()
         .map(|(k, v)| {
-            (
+            Ok((
                 k.to_owned(),
                 Animation::new(
                     handle.clone(),
                     v.0.iter()
-                        .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                        .collect(),
+                        .map(|(name, duration)| {
+                            sprite_index(&sprites_hash, name, "items texture", &path)
+                                .map(|i| (i, *duration))
+                        })
+                        .collect::<Result<_, LoadError>>()?,
                     v.1.clone(),
                 ),
-            )
+            ))
         })
-        .into_iter()
-        .collect();
+        .collect::<Result<_, LoadError>>()?;
 
-    (handle, anim_def)
+    Ok((handle, anim_def))
 }
 
-pub fn load_flavors_texture(world: &mut World) -> (HashMap<String, Animation>) {
+pub fn load_flavors_texture(world: &mut World) -> Result<HashMap<String, Animation>, LoadError> {
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/item/flavors.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
-    let sprites_folder: SpriteFolderDef = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing sprite folder definition: {}", e);
-            panic!("Invalid sprite folder definition <{}>!", path);
-        }
-    };
+    let sprites_folder: SpriteFolderDef = load_ron(&path, "flavors sprite folder")?;
 
     let mut tindex = 300;
     let sprites = sprites_folder
@@ -181,12 +171,12 @@ pub fn load_flavors_texture(world: &mut World) -> (HashMap<String, Animation>) {
 
             tindex += 1;
 
-            let anims: HashMap<String, Animation> = sprites_folder
+            sprites_folder
                 .anims
                 .iter()
                 .map(|(aname, def)| {
                     let (frames, loop_type) = (&def.0, &def.1);
-                    (
+                    Ok((
                         format!("{}_{}", key, aname),
                         Animation::new(
                             handle.clone(),
@@ -194,38 +184,36 @@ pub fn load_flavors_texture(world: &mut World) -> (HashMap<String, Animation>) {
                                 .iter()
                                 .map(|frame| {
                                     let (fname, duration) = (&frame.0, &frame.1);
-                                    (
-                                        *sprites_hash.get(&format!("{}_{}", key, fname)).unwrap(),
-                                        *duration,
+                                    let name = format!("{}_{}", key, fname);
+                                    sprite_index(
+                                        &sprites_hash,
+                                        &name,
+                                        "flavors sprite folder",
+                                        &path,
                                     )
+                                    .map(|i| (i, *duration))
                                 })
-                                .collect(),
+                                .collect::<Result<_, LoadError>>()?,
                             loop_type.clone(),
                         ),
-                    )
+                    ))
                 })
-                .collect();
-            anims
+                .collect::<Result<HashMap<String, Animation>, LoadError>>()
         })
+        .collect::<Result<Vec<_>, LoadError>>()?
+        .into_iter()
         .fold(HashMap::new(), |mut acc, v| {
             acc.extend(v);
             acc
         });
 
-    sprites
+    Ok(sprites)
 }
 
-pub fn load_toppings_texture(world: &mut World) -> (HashMap<String, Animation>) {
+pub fn load_toppings_texture(world: &mut World) -> Result<HashMap<String, Animation>, LoadError> {
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/item/toppings.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
-    let sprites_folder: SpriteFolderDef = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing sprite folder definition: {}", e);
-            panic!("Invalid sprite folder definition <{}>!", path);
-        }
-    };
+    let sprites_folder: SpriteFolderDef = load_ron(&path, "toppings sprite folder")?;
 
     let mut tindex = 400;
     let sprites = sprites_folder
@@ -285,12 +273,12 @@ pub fn load_toppings_texture(world: &mut World) -> (HashMap<String, Animation>)
 
             tindex += 1;
 
-            let anims: HashMap<String, Animation> = sprites_folder
+            sprites_folder
                 .anims
                 .iter()
                 .map(|(aname, def)| {
                     let (frames, loop_type) = (&def.0, &def.1);
-                    (
+                    Ok((
                         format!("{}_{}", key, aname),
                         Animation::new(
                             handle.clone(),
@@ -298,23 +286,28 @@ pub fn load_toppings_texture(world: &mut World) -> (HashMap<String, Animation>)
                                 .iter()
                                 .map(|frame| {
                                     let (fname, duration) = (&frame.0, &frame.1);
-                                    (
-                                        *sprites_hash.get(&format!("{}_{}", key, fname)).unwrap(),
-                                        *duration,
+                                    let name = format!("{}_{}", key, fname);
+                                    sprite_index(
+                                        &sprites_hash,
+                                        &name,
+                                        "toppings sprite folder",
+                                        &path,
                                     )
+                                    .map(|i| (i, *duration))
                                 })
-                                .collect(),
+                                .collect::<Result<_, LoadError>>()?,
                             loop_type.clone(),
                         ),
-                    )
+                    ))
                 })
-                .collect();
-            anims
+                .collect::<Result<HashMap<String, Animation>, LoadError>>()
         })
+        .collect::<Result<Vec<_>, LoadError>>()?
+        .into_iter()
         .fold(HashMap::new(), |mut acc, v| {
             acc.extend(v);
             acc
         });
 
-    sprites
+    Ok(sprites)
 }
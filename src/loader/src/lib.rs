@@ -1,12 +1,20 @@
 mod audio;
 mod buttons;
+mod controls;
 mod data;
+mod error;
 mod fonts;
 mod item;
 mod map;
 mod player;
+mod replay;
+mod report;
 mod ui;
+mod watch;
 
-pub use self::{audio::*, buttons::*, data::*, fonts::*, item::*, map::*, player::*, ui::*};
+pub use self::{
+    audio::*, buttons::*, controls::*, data::*, error::*, fonts::*, item::*, map::*, player::*,
+    replay::*, report::*, ui::*, watch::*,
+};
 
 // TODO: Unify parsing of texture files.
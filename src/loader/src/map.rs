@@ -7,18 +7,21 @@ use amethyst::{
     },
     utils::application_root_dir,
 };
+use crate::error::*;
 use log::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::{collections::HashMap, fs::*};
+use std::collections::HashMap;
 
 pub fn load_map_texture(
     world: &mut World,
-) -> (
-    SpriteSheetHandle,
-    SpriteSheetHandle,
-    HashMap<String, Animation>,
-) {
+) -> Result<
+    (
+        SpriteSheetHandle,
+        SpriteSheetHandle,
+        HashMap<String, Animation>,
+    ),
+    LoadError,
+> {
     info!("Loading Map Textures.");
     let app_root = application_root_dir();
 
@@ -93,17 +96,10 @@ pub fn load_map_texture(
 
     let (handle, mut anim_def) = {
         let path = format!("{}/assets/texture/map_tiles.ron", app_root);
-        let f = File::open(&path).expect("Failed opening file");
         let (tex_def, anim_def): (
             TextureDefinition,
             HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-        ) = match from_reader(f) {
-            Ok(x) => x,
-            Err(e) => {
-                error!("Error parsing texture definition: {}", e);
-                panic!("Invalid texture definition <{}>!", path);
-            }
-        };
+        ) = load_ron(&path, "map tiles texture")?;
 
         let texture = {
             let loader = world.read_resource::<Loader>();
@@ -160,22 +156,24 @@ pub fn load_map_texture(
             .write_resource::<SpriteSheetSet>()
             .insert(MAP_INDEX, handle.clone());
 
-        let mut anim_def: HashMap<String, Animation> = anim_def
+        let anim_def: HashMap<String, Animation> = anim_def
             .iter()
             .map(|(k, v)| {
-                (
+                Ok((
                     k.to_owned(),
                     Animation::new(
                         handle.clone(),
                         v.0.iter()
-                            .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                            .collect(),
+                            .map(|(name, duration)| {
+                                sprite_index(&sprites_hash, name, "map tiles texture", &path)
+                                    .map(|i| (i, *duration))
+                            })
+                            .collect::<Result<_, LoadError>>()?,
                         v.1.clone(),
                     ),
-                )
+                ))
             })
-            .into_iter()
-            .collect();
+            .collect::<Result<_, LoadError>>()?;
         (handle, anim_def)
     };
 
@@ -184,14 +182,7 @@ pub fn load_map_texture(
         .into_iter()
         .map(|folder| {
             let path = format!("{}/assets/texture/{}/index.ron", app_root, folder);
-            let f = File::open(&path).expect("Failed opening file");
-            let sprites_folder: SpriteFolderDef = match from_reader(f) {
-                Ok(x) => x,
-                Err(e) => {
-                    error!("Error parsing sprite folder definition: {}", e);
-                    panic!("Invalid sprite folder definition <{}>!", path);
-                }
-            };
+            let sprites_folder: SpriteFolderDef = load_ron(&path, "sprite folder")?;
 
             let sprites = sprites_folder
                 .keys
@@ -251,12 +242,12 @@ pub fn load_map_texture(
 
                     tindex += 1;
 
-                    let anims: HashMap<String, Animation> = sprites_folder
+                    sprites_folder
                         .anims
                         .iter()
                         .map(|(aname, def)| {
                             let (frames, loop_type) = (&def.0, &def.1);
-                            (
+                            Ok((
                                 format!("{}_{}_{}", folder, key, aname),
                                 Animation::new(
                                     handle.clone(),
@@ -264,27 +255,32 @@ pub fn load_map_texture(
                                         .iter()
                                         .map(|frame| {
                                             let (fname, duration) = (&frame.0, &frame.1);
-                                            (
-                                                *sprites_hash
-                                                    .get(&format!("{}_{}_{}", folder, key, fname))
-                                                    .unwrap(),
-                                                *duration,
+                                            let name = format!("{}_{}_{}", folder, key, fname);
+                                            sprite_index(
+                                                &sprites_hash,
+                                                &name,
+                                                "sprite folder",
+                                                &path,
                                             )
+                                            .map(|i| (i, *duration))
                                         })
-                                        .collect(),
+                                        .collect::<Result<_, LoadError>>()?,
                                     loop_type.clone(),
                                 ),
-                            )
+                            ))
                         })
-                        .collect();
-                    anims
+                        .collect::<Result<HashMap<String, Animation>, LoadError>>()
                 })
+                .collect::<Result<Vec<_>, LoadError>>()?
+                .into_iter()
                 .fold(HashMap::new(), |mut acc, v| {
                     acc.extend(v);
                     acc
                 });
-            sprites
+            Ok(sprites)
         })
+        .collect::<Result<Vec<HashMap<String, Animation>>, LoadError>>()?
+        .into_iter()
         .fold(HashMap::new(), |mut acc, v| {
             acc.extend(v);
             acc
@@ -293,5 +289,38 @@ pub fn load_map_texture(
 
     anim_def.extend(all_anims);
 
-    (handle, empty_handle, anim_def)
+    Ok((handle, empty_handle, anim_def))
+}
+
+/// Loads every map listed in the freeplay collection, maps that fail are skipped and reported.
+pub fn load_freeplay_defs() -> (Vec<MapDefinition>, Vec<LoadError>) {
+    let app_root = application_root_dir();
+    let path = format!("{}/assets/map/freeplay.ron", app_root);
+    let files: Vec<String> = match load_ron(&path, "freeplay collection") {
+        Ok(x) => x,
+        Err(e) => return (Vec::new(), vec![e]),
+    };
+
+    let mut errors = Vec::new();
+    let maps = files
+        .into_iter()
+        .filter_map(|f| {
+            let path = format!("{}/assets/map/{}.ron", app_root, f);
+            let map: MapDefinition = load_ron(&path, "map definition")
+                .map_err(|e| errors.push(e))
+                .ok()?;
+            // Every team member starts on its own spawn, broken maps are left out of the list.
+            let problems = map.validate();
+            if problems.is_empty() {
+                Some(map)
+            } else {
+                errors.extend(problems.into_iter().map(|error| LoadError::InvalidMap {
+                    path: path.clone(),
+                    error,
+                }));
+                None
+            }
+        })
+        .collect();
+    (maps, errors)
 }
@@ -7,25 +7,20 @@ use amethyst::{
     },
     utils::application_root_dir,
 };
+use crate::error::*;
 use log::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::{collections::HashMap, fs::File};
+use std::collections::HashMap;
 
-pub fn load_players_texture(world: &mut World) -> (SpriteSheetHandle, HashMap<String, Animation>) {
+pub fn load_players_texture(
+    world: &mut World,
+) -> Result<(SpriteSheetHandle, HashMap<String, Animation>), LoadError> {
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/player/index.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
     let (tex_def, anim_def): (
         TextureDefinition,
         HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-    ) = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing texture definition: {}", e);
-            panic!("Invalid texture definition <{}>!", path);
-        }
-    };
+    ) = load_ron(&path, "players texture")?;
 
     info!("Loading game texture.");
     // Load Textures
@@ -91,19 +86,21 @@ pub fn load_players_texture(world: &mut World) -> (SpriteSheetHandle, HashMap<St
     let anim_def = anim_def
         .iter()
         .map(|(k, v)| {
-            (
+            Ok((
                 k.to_owned(),
                 Animation::new(
                     handle.clone(),
                     v.0.iter()
-                        .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                        .collect(),
+                        .map(|(name, duration)| {
+                            sprite_index(&sprites_hash, name, "players texture", &path)
+                                .map(|i| (i, *duration))
+                        })
+                        .collect::<Result<_, LoadError>>()?,
                     v.1.clone(),
                 ),
-            )
+            ))
         })
-        .into_iter()
-        .collect();
+        .collect::<Result<_, LoadError>>()?;
 
-    (handle, anim_def)
+    Ok((handle, anim_def))
 }
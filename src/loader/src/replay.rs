@@ -0,0 +1,36 @@
+use crate::error::*;
+use log::*;
+use nk_data::*;
+use ron::ser::{to_string_pretty, PrettyConfig};
+
+pub fn load_replay(path: &str) -> Result<Replay, LoadError> {
+    let replay: Replay = load_ron(path, "replay")?;
+    // Older or newer recordings would play out differently from how they were recorded.
+    if replay.version != REPLAY_VERSION {
+        return Err(LoadError::ReplayVersion {
+            path: path.to_owned(),
+            version: replay.version,
+        });
+    }
+    match replay.map.validate().into_iter().next() {
+        Some(error) => Err(LoadError::InvalidMap {
+            path: path.to_owned(),
+            error,
+        }),
+        None => Ok(replay),
+    }
+}
+
+pub fn save_replay(path: &str, replay: &Replay) {
+    match to_string_pretty(replay, PrettyConfig::default()) {
+        Ok(s) => match std::fs::write(path, s) {
+            Ok(_) => info!(
+                "Saved replay with {} frames to <{}>.",
+                replay.frames.len(),
+                path
+            ),
+            Err(e) => error!("Error writing replay <{}>: {}", path, e),
+        },
+        Err(e) => error!("Error serializing replay: {}", e),
+    }
+}
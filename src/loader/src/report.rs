@@ -0,0 +1,35 @@
+use amethyst::utils::application_root_dir;
+use log::*;
+use nk_data::*;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Unique path under `resources/reports` for the report of a match played with `seed`.
+pub fn match_report_path(seed: u64) -> String {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!(
+        "{}/resources/reports/match_{}_{}.ron",
+        application_root_dir(),
+        time,
+        seed
+    )
+}
+
+pub fn save_match_report(path: &str, report: &MatchReport) {
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            error!("Error creating report directory <{}>: {}", dir.display(), e);
+            return;
+        }
+    }
+    match to_string_pretty(report, PrettyConfig::default()) {
+        Ok(s) => match std::fs::write(path, s) {
+            Ok(_) => info!("Saved match report to <{}>.", path),
+            Err(e) => error!("Error writing match report <{}>: {}", path, e),
+        },
+        Err(e) => error!("Error serializing match report: {}", e),
+    }
+}
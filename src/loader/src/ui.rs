@@ -7,10 +7,10 @@ use amethyst::{
     },
     utils::application_root_dir,
 };
+use crate::error::*;
 use log::*;
 use nk_data::*;
-use ron::de::from_reader;
-use std::{collections::HashMap, fs::File};
+use std::collections::HashMap;
 
 pub fn load_ui_texture(
     world: &mut World,
@@ -78,20 +78,13 @@ pub fn load_ui_texture(
     (data.remove(0), data.remove(0), data.remove(0))
 }
 
-pub fn load_ui_sprites(world: &mut World) -> HashMap<String, Animation> {
+pub fn load_ui_sprites(world: &mut World) -> Result<HashMap<String, Animation>, LoadError> {
     let app_root = application_root_dir();
     let path = format!("{}/assets/texture/other/menu.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
     let (tex_def, anim_def): (
         TextureDefinition,
         HashMap<String, (Vec<(String, f32)>, AnimationLoop)>,
-    ) = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing texture definition: {}", e);
-            panic!("Invalid texture definition <{}>!", path);
-        }
-    };
+    ) = load_ron(&path, "menu texture")?;
 
     info!("Loading game texture.");
     // Load Textures
@@ -153,19 +146,21 @@ pub fn load_ui_sprites(world: &mut World) -> HashMap<String, Animation> {
     let anim_def = anim_def
         .iter()
         .map(|(k, v)| {
-            (
+            Ok((
                 k.to_owned(),
                 Animation::new(
                     handle.clone(),
                     v.0.iter()
-                        .map(|(name, duration)| (*sprites_hash.get(name).unwrap(), *duration))
-                        .collect(),
+                        .map(|(name, duration)| {
+                            sprite_index(&sprites_hash, name, "menu texture", &path)
+                                .map(|i| (i, *duration))
+                        })
+                        .collect::<Result<_, LoadError>>()?,
                     v.1.clone(),
                 ),
-            )
+            ))
         })
-        .into_iter()
-        .collect();
+        .collect::<Result<_, LoadError>>()?;
 
-    anim_def
+    Ok(anim_def)
 }
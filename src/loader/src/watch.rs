@@ -0,0 +1,93 @@
+use amethyst::utils::application_root_dir;
+use nk_data::*;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Kinds of asset files that changed on disk since the last poll.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AssetChanges {
+    pub data: bool,
+    pub palette: bool,
+    pub maps: bool,
+}
+
+impl AssetChanges {
+    pub fn any(&self) -> bool {
+        self.data || self.palette || self.maps
+    }
+}
+
+/// Polls the modification time of the RON files in `assets/data` and `assets/map`, so they can
+/// be reloaded while the game runs with `--dev`.
+#[derive(Debug)]
+pub struct AssetWatcher {
+    data_dir: PathBuf,
+    map_dir: PathBuf,
+    timer: f32,
+    mtimes: HashMap<PathBuf, SystemTime>,
+}
+
+impl AssetWatcher {
+    pub fn new() -> Self {
+        let app_root = application_root_dir();
+        let mut watcher = AssetWatcher {
+            data_dir: PathBuf::from(format!("{}/assets/data", app_root)),
+            map_dir: PathBuf::from(format!("{}/assets/map", app_root)),
+            timer: HOT_RELOAD_INTERVAL,
+            mtimes: HashMap::new(),
+        };
+        watcher.mtimes = watcher.scan().into_iter().collect();
+        watcher
+    }
+
+    /// Checks the disk at most once every `HOT_RELOAD_INTERVAL` seconds.
+    pub fn poll(&mut self, delta: f32) -> AssetChanges {
+        let mut changes = AssetChanges::default();
+
+        self.timer -= delta;
+        if self.timer > 0.0 {
+            return changes;
+        }
+        self.timer = HOT_RELOAD_INTERVAL;
+
+        for (path, mtime) in self.scan() {
+            if self.mtimes.insert(path.clone(), mtime) == Some(mtime) {
+                continue;
+            }
+            if path.starts_with(&self.map_dir) {
+                changes.maps = true;
+            } else if path.file_stem().map_or(false, |s| s == "palette") {
+                changes.palette = true;
+            } else {
+                changes.data = true;
+            }
+        }
+        changes
+    }
+
+    fn scan(&self) -> Vec<(PathBuf, SystemTime)> {
+        [&self.data_dir, &self.map_dir]
+            .iter()
+            .flat_map(|dir| ron_files(dir))
+            .filter_map(|path| {
+                let mtime = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((path, mtime))
+            })
+            .collect()
+    }
+}
+
+fn ron_files(dir: &Path) -> Vec<PathBuf> {
+    match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().map_or(false, |e| e == "ron"))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
@@ -12,19 +12,42 @@ impl<'a, 'b> SystemBundle<'a, 'b> for GameBundle {
         builder.add(InventoryRenderSystem, "xto_inventory_render", &[]);
         builder.add(ControllerSystem::new(), "xto_controller", &[]);
         builder.add(ControlSystem, "xto_control", &["xto_controller"]);
-        builder.add(InputSystem, "xto_input", &["xto_control"]);
-        builder.add(AnimationSystem, "xto_animation", &["xto_control"]);
+        builder.add(KeyboardSystem::default(), "xto_keyboard", &[]);
+        builder.add(AnimationSystem, "xto_animation", &["xto_control", "xto_keyboard"]);
         builder.add(AutotileSystem::default(), "xto_autotile", &["xto_control"]);
+        builder.add(LayerSystem, "xto_layer", &[]);
+        builder.add(InteractionRenderSystem, "xto_interaction_render", &[]);
+        builder.add(ScoreSystem, "xto_score", &[]);
+        builder.add(OrdersSystem, "xto_orders", &[]);
+        builder.add(DeliveryFeedbackSystem::new(), "xto_delivery_feedback", &[]);
+        Ok(())
+    }
+}
+
+/// Systems that simulate a match, without anything that needs a window, GPU or gamepad. They are
+/// dispatched once per fixed `Tick` by whoever owns the match, not once per rendered frame.
+pub struct GameplayBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for GameplayBundle {
+    fn build(self, builder: &mut DispatcherBuilder<'a, 'b>) -> Result<()> {
+        builder.add(ReplayPlaybackSystem, "xto_replay_playback", &[]);
+        builder.add(
+            ReplayRecordSystem,
+            "xto_replay_record",
+            &["xto_replay_playback"],
+        );
+        builder.add(InputSystem, "xto_input", &["xto_replay_record"]);
         builder.add(MovementSystem, "xto_movement", &["xto_input"]);
         builder.add(CollisionSystem, "xto_collision", &["xto_movement"]);
-        builder.add(LayerSystem, "xto_layer", &["xto_collision"]);
         builder.add(InteractSystem, "xto_interact", &["xto_collision"]);
         builder.add(InteractionSystem, "xto_interaction", &["xto_interact"]);
-        builder.add(TimerSystem, "xto_timer", &[]);
-        builder.add(ScoreSystem, "xto_score", &["xto_timer"]);
-        builder.add(GenerateSystem, "xto_generate", &[]);
-        builder.add(OrdersSystem, "xto_orders", &[]);
-        builder.add(MeltSystem, "xto_melt", &[]);
+        builder.add(HissatsuSystem::new(), "xto_hissatsu", &["xto_interaction"]);
+        builder.add(EffectSystem::new(), "xto_effect", &["xto_hissatsu"]);
+        builder.add(StatsSystem::new(), "xto_stats", &["xto_interaction"]);
+        builder.add(TimerSystem, "xto_timer", &["xto_replay_playback"]);
+        builder.add(GenerateSystem, "xto_generate", &["xto_replay_playback"]);
+        builder.add(MeltSystem, "xto_melt", &["xto_effect"]);
+        builder.add(OrderTimerSystem, "xto_order_timer", &["xto_effect"]);
         Ok(())
     }
 }
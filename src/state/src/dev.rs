@@ -0,0 +1,79 @@
+use super::load::palette_colors;
+use amethyst::{core::timing::Time, ecs::prelude::*};
+use log::*;
+use nk_data::*;
+use nk_loader::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// What `hot_reload` went through on this frame, sources that did not change are `None` and
+/// the others hold the problems found, empty when they loaded cleanly.
+#[derive(Debug, Default)]
+pub struct Reloaded {
+    pub data: Option<Vec<LoadError>>,
+    pub palette: Option<Vec<LoadError>>,
+    pub maps: Option<(Vec<MapDefinition>, Vec<LoadError>)>,
+}
+
+/// Reloads changed data, palette and map files while running with `--dev`. Broken files are
+/// logged and the previously loaded version is kept.
+pub fn hot_reload(world: &mut World) -> Reloaded {
+    let mut reloaded = Reloaded::default();
+    let changes = {
+        let delta = world.read_resource::<Time>().delta_seconds();
+        match world.res.try_fetch_mut::<AssetWatcher>() {
+            Some(mut watcher) => watcher.poll(delta),
+            None => return reloaded,
+        }
+    };
+
+    if changes.data {
+        match load_game_data() {
+            Ok(defs) => {
+                info!("Reloaded definitions.");
+                // The first load may have failed, leaving nothing to replace.
+                world.add_resource(defs);
+                reloaded.data = Some(Vec::new());
+            }
+            Err(errors) => {
+                errors
+                    .iter()
+                    .for_each(|e| error!("Keeping previous definitions, {}", e));
+                reloaded.data = Some(errors);
+            }
+        }
+    }
+
+    if changes.palette {
+        match load_palette() {
+            Ok(palette) => {
+                info!("Reloaded palette.");
+                let palette = palette_colors(palette);
+                let shared = world
+                    .res
+                    .try_fetch::<Arc<Mutex<HashMap<String, image::Rgba<u8>>>>>()
+                    .map(|shared| Arc::clone(&shared));
+                match shared {
+                    Some(shared) => *shared.lock().unwrap() = palette,
+                    None => world.add_resource(Arc::new(Mutex::new(palette))),
+                }
+                reloaded.palette = Some(Vec::new());
+            }
+            Err(e) => {
+                error!("Keeping previous palette, {}", e);
+                reloaded.palette = Some(vec![e]);
+            }
+        }
+    }
+
+    if changes.maps {
+        let (maps, errors) = load_freeplay_defs();
+        errors.iter().for_each(|e| error!("{}", e));
+        info!("Reloaded {} freeplay maps.", maps.len());
+        reloaded.maps = Some((maps, errors));
+    }
+
+    reloaded
+}
@@ -1,6 +1,6 @@
 use super::*;
 use amethyst::{
-    assets::Loader,
+    assets::{AssetStorage, Loader},
     core::{
         cgmath::*,
         transform::{GlobalTransform, Parent, ParentHierarchy, Transform},
@@ -24,6 +24,10 @@ use nk_loader::*;
 use nk_util::*;
 use std::iter;
 
+/// Loadout rows after the ingredient grids, one per team, pick the special of that team.
+const SPECIAL_ROWS: usize = 6;
+const LAST_ROW: usize = SPECIAL_ROWS + 1;
+
 pub struct FreePlay {
     flavors: Vec<(FlavorIndex, bool)>,
     preparations: Vec<(PreparationIndex, bool)>,
@@ -32,6 +36,7 @@ pub struct FreePlay {
     selected_flavors: Vec<FlavorIndex>,
     selected_preparation: usize,
     selected_topping: usize,
+    specials: [Option<HissatsuIndex>; 2],
 
     maps: Vec<(MapDefinition, Option<Entity>, bool)>,
     selected_map: usize,
@@ -40,13 +45,25 @@ pub struct FreePlay {
     row: usize,
     page: bool,
 
+    lobby: LobbyConfig,
+
     map_screen: Option<Entity>,
     loadout_screen: Option<Entity>,
     entities: Vec<Entity>,
+    font: Option<FontHandle>,
+    special_lines: Vec<Entity>,
 }
 
 impl Default for FreePlay {
     fn default() -> Self {
+        let (maps, errors) = load_freeplay_defs();
+        errors.iter().for_each(|e| error!("{}", e));
+        FreePlay::with_maps(maps)
+    }
+}
+
+impl FreePlay {
+    pub fn with_maps(maps: Vec<MapDefinition>) -> Self {
         FreePlay {
             flavors: vec![
                 (FlavorIndex(0), true),
@@ -112,20 +129,151 @@ impl Default for FreePlay {
             selected_flavors: Vec::with_capacity(4),
             selected_preparation: 0,
             selected_topping: 0,
+            specials: [None, None],
             row: 0,
             page: false,
 
-            maps: load_freeplay_defs()
-                .into_iter()
-                .map(|m| (m, None, true))
-                .collect(),
+            maps: maps.into_iter().map(|m| (m, None, true)).collect(),
             selected_map: 0,
 
             current_selection: 0,
 
+            lobby: LobbyConfig::default(),
+
             map_screen: None,
             loadout_screen: None,
             entities: Vec::with_capacity(128),
+            font: None,
+            special_lines: Vec::new(),
+        }
+    }
+
+    pub fn with_lobby(mut self, lobby: LobbyConfig) -> Self {
+        self.lobby = lobby;
+        self
+    }
+
+    /// Swaps the map list for freshly loaded maps, keeping the selected index when possible.
+    fn reload_maps(&mut self, world: &mut World, maps: Vec<MapDefinition>) {
+        if maps.is_empty() {
+            warn!("No freeplay maps could be reloaded, keeping the previous ones.");
+            return;
+        }
+
+        let previews = self
+            .maps
+            .drain(..)
+            .filter_map(|(_, e, _)| e)
+            .collect::<Vec<_>>();
+        for preview in previews {
+            let children = world
+                .read_resource::<ParentHierarchy>()
+                .children(preview)
+                .to_vec();
+            world
+                .delete_entities(&children)
+                .expect("Failed to delete map preview!");
+            world
+                .delete_entity(preview)
+                .expect("Failed to delete map preview!");
+        }
+
+        self.maps = maps
+            .into_iter()
+            .map(|m| {
+                let e = create_map_preview(world, &m);
+                (m, Some(e), true)
+            })
+            .collect();
+        self.selected_map = self.selected_map.min(self.maps.len() - 1);
+    }
+
+    /// Each team starts with the first special known by the players of the team definition on
+    /// its side, falling back to the first special there is.
+    fn default_specials(&mut self, defs: &Definitions) {
+        for (side, special) in self.specials.iter_mut().enumerate() {
+            if special.is_some() {
+                continue;
+            }
+            let team = defs.teams().nth(side).map(|t| t.index.clone());
+            *special = defs
+                .players()
+                .filter(|p| team.is_some() && p.team == team)
+                .flat_map(|p| p.hissatsu.iter())
+                .find(|h| defs.get_hissatsu(h).is_some())
+                .cloned()
+                .or_else(|| defs.hissatsu().next().map(|h| h.index.clone()));
+        }
+    }
+
+    fn cycle_special(&mut self, defs: &Definitions, team: usize, forward: bool) {
+        let all = defs.hissatsu().map(|h| h.index.clone()).collect::<Vec<_>>();
+        if all.is_empty() {
+            return;
+        }
+        let current = all
+            .iter()
+            .position(|h| Some(h) == self.specials[team].as_ref())
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % all.len()
+        } else {
+            (current + all.len() - 1) % all.len()
+        };
+        self.specials[team] = Some(all[next].clone());
+    }
+
+    /// Lists the special of each team under the loadout, highlighting the selected row.
+    fn refresh_specials(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.special_lines.as_slice())
+            .expect("Failed to delete special selection!");
+        self.special_lines.clear();
+
+        let font = match (&self.font, self.page) {
+            (Some(font), true) => font.clone(),
+            _ => return,
+        };
+        let lines = {
+            let defs = world.read_resource::<Definitions>();
+            ["left", "right"]
+                .iter()
+                .zip(self.specials.iter())
+                .enumerate()
+                .map(|(team, (side, special))| {
+                    let key = special
+                        .as_ref()
+                        .and_then(|h| defs.get_hissatsu(h))
+                        .map(|h| h.key.as_str())
+                        .unwrap_or("none");
+                    if self.row == SPECIAL_ROWS + team {
+                        (
+                            format!("> {} special: < {} >", side, key),
+                            [1.0, 0.85, 0.2, 1.0],
+                        )
+                    } else {
+                        (format!("{} special: {}", side, key), [1.0, 1.0, 1.0, 1.0])
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let count = lines.len();
+        for (i, (text, color)) in lines.into_iter().enumerate() {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("freeplay_special_{}", i),
+                    Anchor::BottomMiddle,
+                    0.0,
+                    16.0 + 14.0 * (count - 1 - i) as f32,
+                    1.0,
+                    VIEW_WIDTH - 16.0,
+                    14.0,
+                    0,
+                ))
+                .with(UiText::new(font.clone(), text, color, 12.0))
+                .build();
+            self.special_lines.push(entity);
         }
     }
 }
@@ -156,11 +304,21 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
 
         self.map_screen = Some(generate_ui(&mut world, &mut self.entities, &map_ui));
         self.loadout_screen = Some(generate_ui(&mut world, &mut self.entities, &loadout_ui));
+
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+        self.default_specials(&world.read_resource::<Definitions>());
+        self.refresh_specials(&mut world);
     }
 
     fn handle_event(
         &mut self,
-        _data: StateData<GameData>,
+        data: StateData<GameData>,
         event: StateEvent,
     ) -> SimpleTrans<'a, 'b> {
         if let StateEvent::Window(event) = &event {
@@ -199,7 +357,9 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
                                     iter::repeat(self.toppings[self.selected_topping].0.clone())
                                         .take(4)
                                         .collect(),
-                                ),
+                                )
+                                .with_hissatsu(self.specials.to_vec())
+                                .with_lobby(self.lobby.clone()),
                         ));
                     }
                 } else {
@@ -212,7 +372,10 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
                 }
             }
             if is_key_down(&event, VirtualKeyCode::Right) {
-                if self.page {
+                if self.page && self.row >= SPECIAL_ROWS {
+                    let defs = data.world.read_resource::<Definitions>();
+                    self.cycle_special(&defs, self.row - SPECIAL_ROWS, true);
+                } else if self.page {
                     self.current_selection = match self.row {
                         0 => (self.current_selection + 1) % 9,
                         1 => (self.current_selection + 1) % 5,
@@ -231,7 +394,10 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
                 }
             }
             if is_key_down(&event, VirtualKeyCode::Left) {
-                if self.page {
+                if self.page && self.row >= SPECIAL_ROWS {
+                    let defs = data.world.read_resource::<Definitions>();
+                    self.cycle_special(&defs, self.row - SPECIAL_ROWS, false);
+                } else if self.page {
                     self.current_selection = if self.current_selection > 0 {
                         match self.row {
                             0..6 => self.current_selection - 1,
@@ -259,9 +425,8 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
             if is_key_down(&event, VirtualKeyCode::Down) {
                 if self.page {
                     self.row = match self.row {
-                        0..5 => self.row + 1,
-                        5 => 0,
-                        _ => std::unreachable!(),
+                        LAST_ROW => 0,
+                        row => row + 1,
                     };
                     self.current_selection = 0;
                 }
@@ -269,9 +434,8 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
             if is_key_down(&event, VirtualKeyCode::Up) {
                 if self.page {
                     self.row = match self.row {
-                        1..6 => self.row - 1,
-                        0 => 5,
-                        _ => std::unreachable!(),
+                        0 => LAST_ROW,
+                        row => row - 1,
                     };
                     self.current_selection = 0;
                 }
@@ -306,10 +470,12 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
                                 self.selected_topping = self.current_selection;
                             }
                         }
+                        SPECIAL_ROWS..=LAST_ROW => {}
                         _ => std::unreachable!(),
                     };
                 }
             }
+            self.refresh_specials(data.world);
         }
         Trans::None
     }
@@ -325,6 +491,10 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
         //super::update_viewport(camera, world);
         //}
 
+        if let Some((maps, _)) = hot_reload(world).maps {
+            self.reload_maps(world, maps);
+        }
+
         let (
             ui_box_orange,
             ui_box_green,
@@ -567,5 +737,8 @@ impl<'a, 'b> SimpleState<'a, 'b> for FreePlay {
         world
             .delete_entities(self.entities.as_slice())
             .expect("Failed to clean world of FreePlay's entities!");
+        world
+            .delete_entities(self.special_lines.as_slice())
+            .expect("Failed to delete special selection!");
     }
 }
@@ -1,20 +1,24 @@
-use super::*;
+use super::{lobby::*, paused::*, results::*, setup::*, *};
 use amethyst::{
-    assets::Loader,
+    assets::{AssetStorage, Loader},
     core::{
+        bundle::SystemBundle,
         cgmath::*,
+        timing::Time,
         transform::{GlobalTransform, Parent, Transform},
     },
     ecs::prelude::*,
-    input::{is_close_requested, is_key_down},
+    input::{is_close_requested, is_key_down, InputHandler},
     prelude::*,
     renderer::{
         Camera, Hidden, Projection, ScreenDimensions, SpriteRender, Transparent, VirtualKeyCode,
     },
+    shrev::{EventChannel, ReaderId},
     ui::{Anchor, FontAsset, FontHandle, TtfFormat, UiFinder, UiText, UiTransform},
     utils::application_root_dir,
 };
 use either::*;
+use gilrs::ev;
 use log::*;
 use nalgebra::Vector2 as NAVector2;
 use ncollide2d::shape::*;
@@ -22,15 +26,26 @@ use nk_data::*;
 use nk_ecs::*;
 use nk_loader::*;
 use nk_util::*;
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug)]
 pub struct Game {
     map: MapDefinition,
     flavor_loadout: Vec<FlavorIndex>,
     preparation_loadout: Vec<PreparationIndex>,
     topping_loadout: Vec<ToppingIndex>,
+    generation: Option<String>,
+    hissatsu: Vec<Option<HissatsuIndex>>,
+    lobby: LobbyConfig,
     camera: Option<Entity>,
     entities: Vec<Entity>,
+    gameplay: Option<Dispatcher<'static, 'static>>,
+    font: Option<FontHandle>,
+    lost_lines: Vec<Entity>,
+    lost_keys: Vec<String>,
+    freeze: Option<f32>,
+    reader: Option<ReaderId<ev::Event>>,
+    pause_held: bool,
+    choice: PauseChoice,
 }
 
 impl Game {
@@ -40,8 +55,19 @@ impl Game {
             flavor_loadout: Vec::new(),
             preparation_loadout: Vec::new(),
             topping_loadout: Vec::new(),
+            generation: None,
+            hissatsu: Vec::new(),
+            lobby: LobbyConfig::default(),
             camera: None,
             entities: Vec::with_capacity(128),
+            gameplay: None,
+            font: None,
+            lost_lines: Vec::new(),
+            lost_keys: Vec::new(),
+            freeze: None,
+            reader: None,
+            pause_held: false,
+            choice: PauseChoice::Resume,
         }
     }
 
@@ -59,34 +85,89 @@ impl Game {
         self.topping_loadout = toppings;
         self
     }
+
+    pub fn from_replay(replay: &Replay) -> Self {
+        Game::with_map(replay.map.clone())
+            .with_flavors(replay.flavors.clone())
+            .with_preparations(replay.preparations.clone())
+            .with_toppings(replay.toppings.clone())
+            .with_generation(replay.generation.clone())
+            .with_hissatsu(replay.hissatsu.clone())
+            .with_lobby(replay.lobby.clone())
+    }
+
+    /// A new match on the same map with the same loadout and players.
+    pub fn rematch(&self) -> Game {
+        Game::with_map(self.map.clone())
+            .with_flavors(self.flavor_loadout.clone())
+            .with_preparations(self.preparation_loadout.clone())
+            .with_toppings(self.topping_loadout.clone())
+            .with_generation(self.generation.clone())
+            .with_hissatsu(self.hissatsu.clone())
+            .with_lobby(self.lobby.clone())
+    }
+
+    /// Overrides the generation profile chosen by the map.
+    pub fn with_generation(mut self, generation: Option<String>) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Special each team can spend its power meter on, in team index order.
+    pub fn with_hissatsu(mut self, hissatsu: Vec<Option<HissatsuIndex>>) -> Self {
+        self.hissatsu = hissatsu;
+        self
+    }
+
+    /// Players of each side, their roles and the gamepads they use.
+    pub fn with_lobby(mut self, lobby: LobbyConfig) -> Self {
+        let errors = lobby.validate();
+        if errors.is_empty() {
+            self.lobby = lobby;
+        } else {
+            for e in errors {
+                error!("invalid lobby, using the default one: {}", e);
+            }
+        }
+        self
+    }
 }
 
 impl<'a, 'b> SimpleState<'a, 'b> for Game {
     fn on_start(&mut self, data: StateData<GameData>) {
         let StateData { mut world, .. } = data;
 
-        let (left_parent, right_parent) = {
-            let mut left_transform = Transform::default();
-            left_transform.translation = Vector3::new(MAP_OFFSET_X, MAP_OFFSET_Y, 0.0);
-            let left = world
-                .create_entity()
-                .with(left_transform)
-                .with(GlobalTransform::default())
-                .build();
-
-            let mut right_transform = Transform::default();
-            right_transform.translation = Vector3::new(MAP_WIDTH - MAP_OFFSET_X, MAP_OFFSET_Y, 0.0);
-            right_transform.scale = Vector3::new(-1.0, 1.0, 1.0);
-            let right = world
-                .create_entity()
-                .with(right_transform)
-                .with(GlobalTransform::default())
-                .build();
-
-            (left, right)
-        };
+        // Gameplay is stepped by this state at a fixed rate, see `update`.
+        let mut builder = DispatcherBuilder::new();
+        GameplayBundle
+            .build(&mut builder)
+            .expect("Failed to add gameplay systems to dispatcher!");
+        let mut gameplay = builder.build();
+        gameplay.setup(&mut world.res);
+        self.gameplay = Some(gameplay);
+        world.add_resource(Tick::default());
+        world.add_resource(LostInputs::default());
+        world.add_resource(MatchStats::default());
+        world.add_resource(PauseChoice::default());
+        self.reader = Some(
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .register_reader(),
+        );
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+
+        let ((left_parent, right_parent), mut walls) = MatchSetup::create_kitchens(&mut world);
         self.entities.push(left_parent);
         self.entities.push(right_parent);
+        self.entities.append(&mut walls);
         info!("left parent  {:?}", left_parent);
         info!("right parent {:?}", right_parent);
 
@@ -99,84 +180,52 @@ impl<'a, 'b> SimpleState<'a, 'b> for Game {
             &self.topping_loadout[..],
         );
         //self.create_bounds(&mut world, V_W * 2.0, V_H * 2.0);
-        self.create_kitchen_bounds(
-            &mut world,
-            (left_parent, right_parent),
-            (0.0, 0.0, BASE * 10.0, BASE * 11.0),
-        );
 
-        let mut data = Match::default();
-        data.timer = 5.0 * 60.0;
-        data.order_gen_timer = 15.0;
-
-        let team_a = Team {
-            captain: self.create_player(
-                &mut world,
-                0,
-                left_parent,
-                spawn_points[0],
-                0,
-                Style::Full,
-                false,
-                "captain_left",
-            ),
-            server: self.create_player(
-                &mut world,
-                0,
-                left_parent,
-                spawn_points[1],
-                1,
-                Style::Full,
-                false,
-                "server_left",
-            ),
-            scooper_one: None,
-            scooper_two: None,
-            flavors: self.flavor_loadout.clone(),
-            toppings: self.topping_loadout.clone(),
-            preparations: self.preparation_loadout.clone(),
-            power_meter: 0.0,
-            score: 0,
-            orders: vec![],
-            parent: left_parent,
+        let seed = match &*world.read_resource::<ReplayMode>() {
+            ReplayMode::Playing { replay, .. } => MatchSeed(Some(replay.seed)),
+            _ => world
+                .res
+                .try_fetch::<MatchSeed>()
+                .map(|s| s.clone())
+                .unwrap_or_default(),
         };
-
-        let team_b = Team {
-            captain: self.create_player(
-                &mut world,
-                1,
-                right_parent,
-                spawn_points[0],
-                2,
-                Style::HalfLeft,
-                true,
-                "captain_right",
-            ),
-            server: self.create_player(
-                &mut world,
-                1,
-                right_parent,
-                spawn_points[1],
-                2,
-                Style::HalfRight,
-                true,
-                "server_right",
-            ),
-            scooper_one: None,
-            scooper_two: None,
-            flavors: self.flavor_loadout.clone(),
-            toppings: self.topping_loadout.clone(),
-            preparations: self.preparation_loadout.clone(),
-            power_meter: 0.0,
-            score: 0,
-            orders: vec![],
-            parent: right_parent,
+        let rng = MatchRng::from_request(&seed);
+        info!("MATCH SEED: {}", rng.seed());
+        if let ReplayMode::Recording { ref mut replay, .. } = *world.write_resource::<ReplayMode>()
+        {
+            *replay = Replay {
+                version: REPLAY_VERSION,
+                seed: rng.seed(),
+                map: self.map.clone(),
+                flavors: self.flavor_loadout.clone(),
+                preparations: self.preparation_loadout.clone(),
+                toppings: self.topping_loadout.clone(),
+                generation: self.generation.clone(),
+                hissatsu: self.hissatsu.clone(),
+                lobby: self.lobby.clone(),
+                frames: Vec::new(),
+            };
+        }
+        world.add_resource(rng);
+
+        let setup = MatchSetup {
+            map: &self.map,
+            flavors: &self.flavor_loadout,
+            preparations: &self.preparation_loadout,
+            toppings: &self.topping_loadout,
+            generation: self.generation.as_ref(),
+            hissatsu: &self.hissatsu,
+            lobby: &self.lobby,
+            duration: 5.0 * 60.0,
         };
-
-        data.teams.push(team_a);
-        data.teams.push(team_b);
-
-        world.add_resource(data);
+        let entities = &mut self.entities;
+        let mut players = setup.create_match(
+            &mut world,
+            (left_parent, right_parent),
+            &spawn_points,
+            |world, player| entities.append(&mut create_player_sprites(world, player)),
+        );
+        self.entities.append(&mut players);
 
         self.entities.append(&mut map_entities);
 
@@ -211,6 +260,9 @@ impl<'a, 'b> SimpleState<'a, 'b> for Game {
             if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
                 return Trans::Quit;
             }
+            if is_key_down(&event, VirtualKeyCode::Escape) && self.freeze.is_none() {
+                return Trans::Push(Box::new(Paused::new()));
+            }
         }
         Trans::None
     }
@@ -226,11 +278,84 @@ impl<'a, 'b> SimpleState<'a, 'b> for Game {
         //super::update_viewport(camera, world);
         //}
 
+        // The watcher is not polled during a match, so a running match never has its
+        // `Definitions` swapped. Files changed meanwhile are picked up back in FreePlay, the
+        // watcher compares them against the last times it saw them.
+
+        match std::mem::replace(&mut self.choice, PauseChoice::Resume) {
+            PauseChoice::Resume => {}
+            PauseChoice::Restart => return Trans::Switch(Box::new(self.rematch())),
+            PauseChoice::Quit => {
+                let lobby = self.lobby.clone();
+                return Trans::Switch(Box::new(FreePlay::default().with_lobby(lobby)));
+            }
+        }
+
+        let elapsed = world.read_resource::<Time>().delta_seconds();
+
+        // A finished match stays on screen for a moment, then the results are shown.
+        if let Some(freeze) = &mut self.freeze {
+            *freeze -= elapsed;
+            if *freeze > 0.0 {
+                return Trans::None;
+            }
+            let results = match_results(world);
+            return Trans::Switch(Box::new(Results::new(
+                results,
+                self.rematch(),
+                self.lobby.clone(),
+            )));
+        }
+
+        // The match waits for every player that lost its gamepad to get one back.
+        if self.update_lost_input(world) {
+            return Trans::None;
+        }
+
+        if self.pause_requested(world) {
+            return Trans::Push(Box::new(Paused::new()));
+        }
+
+        let ticks = world.write_resource::<Tick>().accumulate(elapsed);
+        if let Some(gameplay) = &mut self.gameplay {
+            for _ in 0..ticks {
+                gameplay.dispatch(&world.res);
+                world.maintain();
+                world.write_resource::<Tick>().advance();
+                if world.read_resource::<Match>().is_over() {
+                    let report = match_report(world);
+                    info!("match over, scores {:?}", report.scores);
+                    save_match_report(&match_report_path(report.seed), &report);
+                    self.freeze = Some(MATCH_END_FREEZE);
+                    break;
+                }
+            }
+        }
+
         Trans::None
     }
 
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        self.choice = std::mem::replace(
+            &mut *world.write_resource::<PauseChoice>(),
+            PauseChoice::Resume,
+        );
+        // Buttons pressed in the pause menu are not meant for the match.
+        self.pause_requested(world);
+        for input in (&mut world.write_storage::<Input>()).join() {
+            *input = Input::new();
+        }
+    }
+
     fn on_stop(&mut self, data: StateData<GameData>) {
         let StateData { world, .. } = data;
+        self.gameplay = None;
+        world.write_resource::<LostInputs>().players.clear();
+        self.clear_lost_input(world);
+        if let ReplayMode::Recording { path, replay } = &*world.read_resource::<ReplayMode>() {
+            save_replay(path, replay);
+        }
         world
             .delete_entities(self.entities.as_slice())
             .expect("Failed to clean world of Game's entities!");
@@ -238,159 +363,77 @@ impl<'a, 'b> SimpleState<'a, 'b> for Game {
 }
 
 impl Game {
-    fn create_player(
-        &mut self,
-        world: &mut World,
-        team_index: usize,
-        parent: Entity,
-        (x, y): (f32, f32),
-        gamepad_index: usize,
-        gamepad_style: Style,
-        invert_x_axis: bool,
-        key: &str,
-    ) -> Entity {
-        let (player_handle, items_handle) = {
-            let handles = world.read_resource::<Handles>();
-            (handles.player_handle.clone(), handles.items_handle.clone())
+    /// Shows which players lost input, returns true while the match has to wait for them.
+    fn update_lost_input(&mut self, world: &mut World) -> bool {
+        let keys = {
+            let lost = world.read_resource::<LostInputs>();
+            let players = world.read_storage::<Player>();
+            lost.players
+                .iter()
+                .filter_map(|(e, _)| players.get(*e).map(|p| p.palette_key.clone()))
+                .collect::<Vec<_>>()
         };
+        if keys != self.lost_keys {
+            self.clear_lost_input(world);
+            if let Some(font) = &self.font {
+                let mut lines = keys
+                    .iter()
+                    .map(|k| (format!("{} lost input", k), [1.0, 0.3, 0.3, 1.0]))
+                    .collect::<Vec<_>>();
+                if !lines.is_empty() {
+                    lines.push((
+                        "Connect a gamepad to continue".to_owned(),
+                        [1.0, 1.0, 1.0, 1.0],
+                    ));
+                }
+                self.lost_lines = create_text_lines(world, font, "lost_input", lines);
+            }
+            self.lost_keys = keys;
+        }
+        !self.lost_keys.is_empty()
+    }
 
-        let mut transform = Transform::default();
-        transform.translation = Vector3::new(x, y, 0.0);
-
-        let entity = world
-            .create_entity()
-            .with(Direction {
-                current: Cardinal::South,
-                previous: None,
-                current_anim: "idle".to_string(),
-            })
-            .with(SpriteRender {
-                sprite_sheet: player_handle,
-                sprite_number: 0,
-                flip_horizontal: false,
-                flip_vertical: false,
-            })
-            .with(Player::new_with_style(
-                gamepad_index,
-                gamepad_style,
-                team_index,
-                0.0,
-                invert_x_axis,
-                key.to_owned(),
-            ))
-            .with(Input::new())
-            .with(Velocity::new(80.0))
-            .with(Hitbox {
-                shape: Either::Left(Cuboid::new(NAVector2::new(
-                    PLAYER_HITBOX_WIDTH / 2.0,
-                    PLAYER_HITBOX_HEIGHT / 2.0,
-                ))),
-                offset: NAVector2::new(0.0, 0.0),
+    /// True if a player pressed Start on the gamepad assigned to it, or the pause key of its
+    /// keyboard half, since the last call.
+    fn pause_requested(&mut self, world: &World) -> bool {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => return false,
+        };
+        let pressed = world
+            .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+            .lock()
+            .unwrap()
+            .read(reader)
+            .filter_map(|e| match e.event {
+                ev::EventType::ButtonPressed(ev::Button::Start, _) => Some(e.id),
+                _ => None,
             })
-            .with(Transparent)
-            .with(Layered)
-            .with(transform)
-            .with(Parent { entity: parent })
-            .with(GlobalTransform::default())
-            .build();
-        self.entities.push(entity);
-
-        let mut item_transform = Transform::default();
-        item_transform.translation = Vector3::new(0.0, 28.0, 1.0);
-
-        let item_parent = world
-            .create_entity()
-            .with(Parent { entity })
-            .with(item_transform)
-            .with(GlobalTransform::default())
-            .build();
+            .collect::<Vec<_>>();
+        let players = world.read_storage::<Player>();
+        let gamepad = pressed.iter().any(|id| {
+            (&players)
+                .join()
+                .any(|p| p.device == InputDevice::Gamepad(*id))
+        });
 
-        (0..7).for_each(|i| {
-            let carry_me = world
-                .create_entity()
-                .with(SpriteRender {
-                    sprite_sheet: items_handle.clone(),
-                    sprite_number: 0,
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                })
-                .with(InventoryItem(i))
-                .with(Transparent)
-                .with(Hidden)
-                .with(Parent {
-                    entity: item_parent,
-                })
-                .with(Transform::default())
-                .with(GlobalTransform::default())
-                .build();
-            self.entities.push(carry_me);
+        // Keyboard actions are only reported as held, so a press is a change from released.
+        let handler = world.read_resource::<InputHandler<String, String>>();
+        let held = (&players).join().any(|p| {
+            p.device == InputDevice::Keyboard && keyboard_pause_down(&handler, p.gamepad_style)
         });
+        let keyboard = held && !self.pause_held;
+        self.pause_held = held;
 
-        entity
+        gamepad || keyboard
     }
 
-    fn create_kitchen_bounds(
-        &mut self,
-        world: &mut World,
-        (left_parent, right_parent): (Entity, Entity),
-        (x, y, width, height): (f32, f32, f32, f32),
-    ) {
-        let xs = vec![x + width / 2.0, x + width / 2.0, x - BASE, x + width + BASE];
-        let ys = vec![
-            y - BASE,
-            y + height + BASE,
-            y + height / 2.0,
-            y + height / 2.0,
-        ];
-        let ws = vec![
-            width + BASE * 2.0,
-            width + BASE * 2.0,
-            BASE * 2.0,
-            BASE * 2.0,
-        ];
-        let hs = vec![
-            BASE * 2.0,
-            BASE * 2.0,
-            height + BASE * 2.0,
-            height + BASE * 2.0,
-        ];
-
-        for (((x, y), w), h) in xs.iter().zip(ys).zip(ws).zip(hs) {
-            let mut transform = Transform::default();
-            transform.translation = Vector3::new(*x, y, 0.0);
-
-            self.entities.push(
-                world
-                    .create_entity()
-                    .with(Solid)
-                    .with(Hitbox {
-                        shape: Either::Left(Cuboid::new(NAVector2::new(w / 2.0, h / 2.0))),
-                        offset: NAVector2::new(0.0, 0.0),
-                    })
-                    .with(transform.clone())
-                    .with(Parent {
-                        entity: left_parent,
-                    })
-                    .with(GlobalTransform::default())
-                    .build(),
-            );
-
-            self.entities.push(
-                world
-                    .create_entity()
-                    .with(Solid)
-                    .with(Hitbox {
-                        shape: Either::Left(Cuboid::new(NAVector2::new(w / 2.0, h / 2.0))),
-                        offset: NAVector2::new(0.0, 0.0),
-                    })
-                    .with(transform)
-                    .with(Parent {
-                        entity: right_parent,
-                    })
-                    .with(GlobalTransform::default())
-                    .build(),
-            );
-        }
+    fn clear_lost_input(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.lost_lines.as_slice())
+            .expect("Failed to delete lost input text!");
+        self.lost_lines.clear();
+        self.lost_keys.clear();
     }
 
     fn create_bounds(&mut self, world: &mut World, width: f32, height: f32) {
@@ -452,72 +495,79 @@ impl Game {
             .with(transform)
             .with(GlobalTransform::default())
             .build();
+        self.entities.push(timer_parent);
 
         // Timer Minutes
         (0..2).for_each(|i| {
             let mut transform = Transform::default();
             transform.translation.x = 17.0 * i as f32;
 
-            world
-                .create_entity()
-                .with(TimerDigit(i))
-                .with(Parent {
-                    entity: timer_parent,
-                })
-                .with(SpriteRender {
-                    sprite_sheet: timer_font.clone(),
-                    sprite_number: 0,
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                })
-                .with(Transparent)
-                .with(transform)
-                .with(GlobalTransform::default())
-                .build();
+            self.entities.push(
+                world
+                    .create_entity()
+                    .with(TimerDigit(i))
+                    .with(Parent {
+                        entity: timer_parent,
+                    })
+                    .with(SpriteRender {
+                        sprite_sheet: timer_font.clone(),
+                        sprite_number: 0,
+                        flip_horizontal: false,
+                        flip_vertical: false,
+                    })
+                    .with(Transparent)
+                    .with(transform)
+                    .with(GlobalTransform::default())
+                    .build(),
+            );
         });
 
         // Timer Colon
         let mut transform = Transform::default();
         transform.translation.x = 28.0;
 
-        world
-            .create_entity()
-            .with(TimerDigit(4))
-            .with(Parent {
-                entity: timer_parent,
-            })
-            .with(SpriteRender {
-                sprite_sheet: timer_font.clone(),
-                sprite_number: 10,
-                flip_horizontal: false,
-                flip_vertical: false,
-            })
-            .with(Transparent)
-            .with(transform)
-            .with(GlobalTransform::default())
-            .build();
-
-        // Timer Seconds
-        (2..4).for_each(|i| {
-            let mut transform = Transform::default();
-            transform.translation.x = 6.0 + (17.0 * i as f32);
-
+        self.entities.push(
             world
                 .create_entity()
-                .with(TimerDigit(i))
+                .with(TimerDigit(4))
                 .with(Parent {
                     entity: timer_parent,
                 })
                 .with(SpriteRender {
                     sprite_sheet: timer_font.clone(),
-                    sprite_number: 0,
+                    sprite_number: 10,
                     flip_horizontal: false,
                     flip_vertical: false,
                 })
                 .with(Transparent)
                 .with(transform)
                 .with(GlobalTransform::default())
-                .build();
+                .build(),
+        );
+
+        // Timer Seconds
+        (2..4).for_each(|i| {
+            let mut transform = Transform::default();
+            transform.translation.x = 6.0 + (17.0 * i as f32);
+
+            self.entities.push(
+                world
+                    .create_entity()
+                    .with(TimerDigit(i))
+                    .with(Parent {
+                        entity: timer_parent,
+                    })
+                    .with(SpriteRender {
+                        sprite_sheet: timer_font.clone(),
+                        sprite_number: 0,
+                        flip_horizontal: false,
+                        flip_vertical: false,
+                    })
+                    .with(Transparent)
+                    .with(transform)
+                    .with(GlobalTransform::default())
+                    .build(),
+            );
         });
 
         // Score Left
@@ -531,27 +581,30 @@ impl Game {
             .with(transform)
             .with(GlobalTransform::default())
             .build();
+        self.entities.push(score_left_parent);
 
         (0..8).for_each(|i| {
             let mut transform = Transform::default();
             transform.translation.x = 15.0 * i as f32;
 
-            world
-                .create_entity()
-                .with(ScoreDigit(i, false))
-                .with(Parent {
-                    entity: score_left_parent,
-                })
-                .with(SpriteRender {
-                    sprite_sheet: score_font.clone(),
-                    sprite_number: 0,
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                })
-                .with(Transparent)
-                .with(transform)
-                .with(GlobalTransform::default())
-                .build();
+            self.entities.push(
+                world
+                    .create_entity()
+                    .with(ScoreDigit(i, false))
+                    .with(Parent {
+                        entity: score_left_parent,
+                    })
+                    .with(SpriteRender {
+                        sprite_sheet: score_font.clone(),
+                        sprite_number: 0,
+                        flip_horizontal: false,
+                        flip_vertical: false,
+                    })
+                    .with(Transparent)
+                    .with(transform)
+                    .with(GlobalTransform::default())
+                    .build(),
+            );
         });
 
         // Score Right
@@ -565,27 +618,30 @@ impl Game {
             .with(transform)
             .with(GlobalTransform::default())
             .build();
+        self.entities.push(score_right_parent);
 
         (0..8).for_each(|i| {
             let mut transform = Transform::default();
             transform.translation.x = 15.0 * i as f32;
 
-            world
-                .create_entity()
-                .with(ScoreDigit(i, true))
-                .with(Parent {
-                    entity: score_right_parent,
-                })
-                .with(SpriteRender {
-                    sprite_sheet: score_font.clone(),
-                    sprite_number: 0,
-                    flip_horizontal: false,
-                    flip_vertical: false,
-                })
-                .with(Transparent)
-                .with(transform)
-                .with(GlobalTransform::default())
-                .build();
+            self.entities.push(
+                world
+                    .create_entity()
+                    .with(ScoreDigit(i, true))
+                    .with(Parent {
+                        entity: score_right_parent,
+                    })
+                    .with(SpriteRender {
+                        sprite_sheet: score_font.clone(),
+                        sprite_number: 0,
+                        flip_horizontal: false,
+                        flip_vertical: false,
+                    })
+                    .with(Transparent)
+                    .with(transform)
+                    .with(GlobalTransform::default())
+                    .build(),
+            );
         });
     }
 
@@ -609,6 +665,7 @@ impl Game {
                     .with(transform)
                     .with(GlobalTransform::default())
                     .build();
+                self.entities.push(parent);
 
                 vec![
                     (0, 0.0, 0.0),
@@ -622,21 +679,23 @@ impl Game {
                     transform.translation.x = x;
                     transform.translation.y = y;
 
-                    world
-                        .create_entity()
-                        .with(OrderIngredient(i))
-                        .with(Parent { entity: parent })
-                        .with(SpriteRender {
-                            sprite_sheet: score_font.clone(),
-                            sprite_number: 0,
-                            flip_horizontal: false,
-                            flip_vertical: false,
-                        })
-                        //.with(Hidden)
-                        .with(Transparent)
-                        .with(transform)
-                        .with(GlobalTransform::default())
-                        .build();
+                    self.entities.push(
+                        world
+                            .create_entity()
+                            .with(OrderIngredient(i))
+                            .with(Parent { entity: parent })
+                            .with(SpriteRender {
+                                sprite_sheet: score_font.clone(),
+                                sprite_number: 0,
+                                flip_horizontal: false,
+                                flip_vertical: false,
+                            })
+                            //.with(Hidden)
+                            .with(Transparent)
+                            .with(transform)
+                            .with(GlobalTransform::default())
+                            .build(),
+                    );
                 });
             });
 
@@ -655,6 +714,7 @@ impl Game {
                     .with(transform)
                     .with(GlobalTransform::default())
                     .build();
+                self.entities.push(parent);
 
                 vec![
                     (0, 0.0, 0.0),
@@ -668,22 +728,96 @@ impl Game {
                     transform.translation.x = x;
                     transform.translation.y = y;
 
-                    world
-                        .create_entity()
-                        .with(OrderIngredient(i))
-                        .with(Parent { entity: parent })
-                        .with(SpriteRender {
-                            sprite_sheet: score_font.clone(),
-                            sprite_number: 0,
-                            flip_horizontal: false,
-                            flip_vertical: false,
-                        })
-                        //.with(Hidden)
-                        .with(Transparent)
-                        .with(transform)
-                        .with(GlobalTransform::default())
-                        .build();
+                    self.entities.push(
+                        world
+                            .create_entity()
+                            .with(OrderIngredient(i))
+                            .with(Parent { entity: parent })
+                            .with(SpriteRender {
+                                sprite_sheet: score_font.clone(),
+                                sprite_number: 0,
+                                flip_horizontal: false,
+                                flip_vertical: false,
+                            })
+                            //.with(Hidden)
+                            .with(Transparent)
+                            .with(transform)
+                            .with(GlobalTransform::default())
+                            .build(),
+                    );
                 });
             });
     }
 }
+
+/// Draws a player created by `MatchSetup`, along with the sprites of what it carries. Returns the
+/// new sprite entities.
+fn create_player_sprites(world: &mut World, player: Entity) -> Vec<Entity> {
+    let (player_handle, items_handle) = {
+        let handles = world.read_resource::<Handles>();
+        (handles.player_handle.clone(), handles.items_handle.clone())
+    };
+
+    world
+        .write_storage::<SpriteRender>()
+        .insert(
+            player,
+            SpriteRender {
+                sprite_sheet: player_handle,
+                sprite_number: 0,
+                flip_horizontal: false,
+                flip_vertical: false,
+            },
+        )
+        .expect("Failed to add sprite to player!");
+    world
+        .write_storage::<Transparent>()
+        .insert(player, Transparent)
+        .expect("Failed to make player transparent!");
+    world
+        .write_storage::<Layered>()
+        .insert(player, Layered)
+        .expect("Failed to layer player!");
+
+    let mut entities = Vec::with_capacity(8);
+    let mut item_transform = Transform::default();
+    item_transform.translation = Vector3::new(0.0, 28.0, 1.0);
+
+    let item_parent = world
+        .create_entity()
+        .with(Parent { entity: player })
+        .with(item_transform)
+        .with(GlobalTransform::default())
+        .build();
+    entities.push(item_parent);
+
+    // One sprite per topping the biggest preparation takes, after the fixed ones.
+    let toppings = world
+        .read_resource::<Definitions>()
+        .preparations()
+        .map(|p| p.topping_capacity())
+        .max()
+        .unwrap_or(0);
+    (0..6 + toppings).for_each(|i| {
+        let carry_me = world
+            .create_entity()
+            .with(SpriteRender {
+                sprite_sheet: items_handle.clone(),
+                sprite_number: 0,
+                flip_horizontal: false,
+                flip_vertical: false,
+            })
+            .with(InventoryItem(i))
+            .with(Transparent)
+            .with(Hidden)
+            .with(Parent {
+                entity: item_parent,
+            })
+            .with(Transform::default())
+            .with(GlobalTransform::default())
+            .build();
+        entities.push(carry_me);
+    });
+
+    entities
+}
@@ -0,0 +1,223 @@
+use super::{setup::*, *};
+use amethyst::{
+    core::{bundle::SystemBundle, transform::TransformBundle},
+    ecs::prelude::*,
+};
+use log::*;
+use nk_data::*;
+use nk_ecs::*;
+use nk_util::*;
+
+/// Describes a match to be run by a `Simulation`, mirrors the options of the `Game` state.
+#[derive(Debug)]
+pub struct SimulationSetup {
+    map: MapDefinition,
+    flavor_loadout: Vec<FlavorIndex>,
+    preparation_loadout: Vec<PreparationIndex>,
+    topping_loadout: Vec<ToppingIndex>,
+    generation: Option<String>,
+    hissatsu: Vec<Option<HissatsuIndex>>,
+    lobby: LobbyConfig,
+    seed: Option<u64>,
+    timestep: f32,
+    duration: f32,
+}
+
+impl SimulationSetup {
+    pub fn with_map(map: MapDefinition) -> Self {
+        SimulationSetup {
+            map,
+            flavor_loadout: Vec::new(),
+            preparation_loadout: Vec::new(),
+            topping_loadout: Vec::new(),
+            generation: None,
+            hissatsu: Vec::new(),
+            lobby: LobbyConfig::default(),
+            seed: None,
+            timestep: TICK_DELTA,
+            duration: 5.0 * 60.0,
+        }
+    }
+
+    pub fn with_flavors(mut self, flavors: Vec<FlavorIndex>) -> Self {
+        self.flavor_loadout = flavors;
+        self
+    }
+
+    pub fn with_preparations(mut self, preparations: Vec<PreparationIndex>) -> Self {
+        self.preparation_loadout = preparations;
+        self
+    }
+
+    pub fn with_toppings(mut self, toppings: Vec<ToppingIndex>) -> Self {
+        self.topping_loadout = toppings;
+        self
+    }
+
+    pub fn with_generation(mut self, generation: Option<String>) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Special of each team, in team index order.
+    pub fn with_hissatsu(mut self, hissatsu: Vec<Option<HissatsuIndex>>) -> Self {
+        self.hissatsu = hissatsu;
+        self
+    }
+
+    /// An invalid lobby is ignored and the default one is kept.
+    pub fn with_lobby(mut self, lobby: LobbyConfig) -> Self {
+        let errors = lobby.validate();
+        if errors.is_empty() {
+            self.lobby = lobby;
+        } else {
+            for e in errors {
+                error!("invalid lobby, using the default one: {}", e);
+            }
+        }
+        self
+    }
+
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Length in seconds of every simulation step.
+    pub fn with_timestep(mut self, timestep: f32) -> Self {
+        self.timestep = timestep;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn build<'a, 'b>(self, defs: Definitions) -> Simulation<'a, 'b> {
+        Simulation::new(self, defs)
+    }
+}
+
+/// Runs a match without window, renderer, audio or gamepads, advancing it in fixed timesteps.
+/// Players are driven by writing into their `Input` components between steps.
+pub struct Simulation<'a, 'b> {
+    world: World,
+    dispatcher: Dispatcher<'a, 'b>,
+    players: Vec<Entity>,
+}
+
+impl<'a, 'b> Simulation<'a, 'b> {
+    fn new(setup: SimulationSetup, defs: Definitions) -> Self {
+        let mut world = World::new();
+        world.add_resource(defs);
+
+        let mut builder = DispatcherBuilder::new();
+        GameplayBundle
+            .build(&mut builder)
+            .expect("Failed to add gameplay systems to headless dispatcher!");
+        TransformBundle::new()
+            .with_dep(&["xto_movement"])
+            .build(&mut builder)
+            .expect("Failed to add transform systems to headless dispatcher!");
+        let mut dispatcher = builder.build();
+        dispatcher.setup(&mut world.res);
+
+        let rng = MatchRng::from_request(&MatchSeed(setup.seed));
+        info!("HEADLESS MATCH SEED: {}", rng.seed());
+        world.add_resource(rng);
+        world.add_resource(Tick::new(setup.timestep));
+
+        let mut simulation = Simulation {
+            world,
+            dispatcher,
+            players: Vec::with_capacity(4),
+        };
+        simulation.create_match(&setup);
+        simulation
+    }
+
+    /// Advances the match by one fixed timestep.
+    pub fn step(&mut self) {
+        self.dispatcher.dispatch(&self.world.res);
+        self.world.maintain();
+        self.world.write_resource::<Tick>().advance();
+    }
+
+    /// Steps until the match timer runs out, returning the amount of steps taken.
+    pub fn run_to_end(&mut self) -> u64 {
+        let start = self.steps();
+        while !self.is_finished() {
+            self.step();
+        }
+        self.steps() - start
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.world.read_resource::<Match>().is_over()
+    }
+
+    pub fn results(&self) -> MatchResults {
+        match_results(&self.world)
+    }
+
+    pub fn report(&self) -> MatchReport {
+        match_report(&self.world)
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.world.read_resource::<Tick>().frame()
+    }
+
+    pub fn players(&self) -> &[Entity] {
+        &self.players
+    }
+
+    /// Overwrites the input of a player for the next steps, as a bot or test driver would.
+    pub fn set_input(&mut self, player: Entity, input: &RecordedInput) {
+        if let Some(i) = self.world.write_storage::<Input>().get_mut(player) {
+            i.apply(input);
+        }
+    }
+
+    pub fn scores(&self) -> Vec<isize> {
+        self.world
+            .read_resource::<Match>()
+            .teams
+            .iter()
+            .map(|t| t.score)
+            .collect()
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    fn create_match(&mut self, setup: &SimulationSetup) {
+        let (parents, _) = MatchSetup::create_kitchens(&mut self.world);
+        let (_, spawn_points) = create_map_headless(
+            &mut self.world,
+            parents,
+            &setup.map,
+            &setup.flavor_loadout[..],
+            &setup.preparation_loadout[..],
+            &setup.topping_loadout[..],
+        );
+
+        self.players = MatchSetup {
+            map: &setup.map,
+            flavors: &setup.flavor_loadout,
+            preparations: &setup.preparation_loadout,
+            toppings: &setup.topping_loadout,
+            generation: setup.generation.as_ref(),
+            hissatsu: &setup.hissatsu,
+            lobby: &setup.lobby,
+            duration: setup.duration,
+        }
+        .create_match(&mut self.world, parents, &spawn_points, |_, _| {});
+    }
+}
@@ -10,9 +10,16 @@ use amethyst::{
 use log::*;
 
 mod bundle;
+mod dev;
 mod freeplay;
 mod game;
+mod headless;
 mod load;
+mod lobby;
+mod paused;
+mod remap;
+mod results;
+mod setup;
 
 const V_W: f32 = 240.0;
 const V_H: f32 = 136.0;
@@ -77,4 +84,7 @@ pub fn update_viewport(camera: Entity, world: &mut World) -> Entity {
     camera
 }
 
-pub use self::{bundle::*, freeplay::*, game::*, load::*};
+pub use self::{
+    bundle::*, dev::*, freeplay::*, game::*, headless::*, load::*, lobby::*, paused::*, remap::*,
+    results::*,
+};
@@ -1,6 +1,6 @@
-use super::freeplay::*;
+use super::{dev::Reloaded, game::*, lobby::*};
 use amethyst::{
-    assets::Loader,
+    assets::{AssetStorage, Loader},
     core::{
         cgmath::*,
         transform::{GlobalTransform, Transform},
@@ -10,7 +10,6 @@ use amethyst::{
     prelude::*,
     renderer::{Camera, Projection, SpriteRender, Transparent, VirtualKeyCode},
     ui::{Anchor, FontAsset, FontHandle, TtfFormat, UiFinder, UiText, UiTransform},
-    utils::application_root_dir,
 };
 use clap::ArgMatches;
 use log::*;
@@ -18,17 +17,27 @@ use nk_data::*;
 use nk_ecs::*;
 use nk_loader::*;
 use nk_util::*;
-use ron::de::from_reader;
 use std::{
     collections::HashMap,
-    fs::File,
     sync::{Arc, Mutex},
 };
 
+/// Files a load error came from, so a hot reload can replace the errors of what it reloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Assets,
+    Palette,
+    Data,
+    Maps,
+}
+
 #[derive(Debug, Default)]
 pub struct Load {
     camera: Option<Entity>,
     entities: Vec<Entity>,
+    error_lines: Vec<Entity>,
+    maps: Vec<MapDefinition>,
+    errors: Vec<(Source, LoadError)>,
 }
 
 impl<'a, 'b> SimpleState<'a, 'b> for Load {
@@ -57,62 +66,104 @@ impl<'a, 'b> SimpleState<'a, 'b> for Load {
         self.camera = Some(camera);
 
         initialise_audio(&mut world);
-        let (player_handle, mut animations) = load_players_texture(&mut world);
-        let (items_handle, items_anims) = load_items_texture(&mut world);
-        let flavors_anims = load_flavors_texture(&mut world);
-        let toppings_anims = load_toppings_texture(&mut world);
-        let (map_handle, empty_handle, map_anims) = load_map_texture(&mut world);
+        let mut errors = Vec::new();
+        let players = load_players_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
+        let items = load_items_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
+        let flavors = load_flavors_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
+        let toppings = load_toppings_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
+        let map = load_map_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
         let (bg_handle, hud_handle, title_handle) = load_ui_texture(&mut world);
-        let (buttons_handle, progress_handle, interaction_anims) =
-            load_interaction_texture(&mut world);
+        let interaction = load_interaction_texture(&mut world)
+            .map_err(|e| errors.push(e))
+            .ok();
         let (score_font, timer_font) = load_number_fonts(&mut world);
-        let ui_anims = load_ui_sprites(&mut world);
-
-        world.add_resource(Handles {
-            player_handle,
-            items_handle,
-            map_handle,
-            bg_handle,
-            empty_handle,
-            hud_handle,
-            buttons_handle,
-            progress_handle,
-            score_font,
-            timer_font,
-        });
-
-        animations.extend(items_anims);
-        animations.extend(flavors_anims);
-        animations.extend(toppings_anims);
-        animations.extend(map_anims);
-        animations.extend(interaction_anims);
-        animations.extend(ui_anims);
-        info!("Loaded Animations: {:?}", animations);
-        world.add_resource(Animations { animations });
-
-        generate_bg(&mut world);
-        info!("Generated background!");
-
-        let palette: HashMap<String, image::Rgba<u8>> = {
-            let path = format!("{}/assets/data/palette.ron", application_root_dir());
-            let f = File::open(&path).expect("Failed opening 'palette.ron' file!");
-            match from_reader(f): Result<[(String, u8, u8, u8, u8); 8], _> {
-                Ok(x) => x
-                    .iter()
-                    .map(|x| (x.0.clone(), image::Rgba([x.2, x.3, x.4, x.1])))
-                    .collect(),
-                Err(e) => {
-                    error!("Error parsing palette definition: {}", e);
-                    panic!("Invalid palette definition <{}>!", path);
-                }
+        let ui = load_ui_sprites(&mut world).map_err(|e| errors.push(e)).ok();
+        let mut errors: Vec<_> = errors.into_iter().map(|e| (Source::Assets, e)).collect();
+
+        if let (
+            Some((player_handle, mut animations)),
+            Some((items_handle, items_anims)),
+            Some(flavors_anims),
+            Some(toppings_anims),
+            Some((map_handle, empty_handle, map_anims)),
+            Some((buttons_handle, progress_handle, interaction_anims)),
+            Some(ui_anims),
+        ) = (players, items, flavors, toppings, map, interaction, ui)
+        {
+            world.add_resource(Handles {
+                player_handle,
+                items_handle,
+                map_handle,
+                bg_handle,
+                empty_handle,
+                hud_handle,
+                buttons_handle,
+                progress_handle,
+                score_font,
+                timer_font,
+            });
+
+            animations.extend(items_anims);
+            animations.extend(flavors_anims);
+            animations.extend(toppings_anims);
+            animations.extend(map_anims);
+            animations.extend(interaction_anims);
+            animations.extend(ui_anims);
+            info!("Loaded Animations: {:?}", animations);
+            world.add_resource(Animations { animations });
+
+            generate_bg(&mut world);
+            info!("Generated background!");
+        }
+
+        match load_palette() {
+            Ok(palette) => {
+                let palette = palette_colors(palette);
+                info!("Loaded palette: {:?}", palette);
+                world.add_resource(Arc::new(Mutex::new(palette)));
             }
-        };
-        info!("Loaded palette: {:?}", palette);
-        world.add_resource(Arc::new(Mutex::new(palette)));
+            Err(e) => errors.push((Source::Palette, e)),
+        }
 
-        let defs = load_game_data();
-        info!("Loaded definitions: {:?}", defs);
-        world.add_resource(defs);
+        // Broken user controls should not keep anyone from playing with the defaults.
+        match load_controls() {
+            Ok(controls) => world.add_resource(controls),
+            Err(e) => {
+                error!("{}, using the default controls", e);
+                world.add_resource(ControlsConfig::default());
+            }
+        }
+
+        match load_game_data() {
+            Ok(defs) => {
+                info!("Loaded definitions: {:?}", defs);
+                world.add_resource(defs);
+            }
+            Err(e) => errors.extend(e.into_iter().map(|e| (Source::Data, e))),
+        }
+
+        let (maps, map_errors) = load_freeplay_defs();
+        errors.extend(map_errors.into_iter().map(|e| (Source::Maps, e)));
+        self.maps = maps;
+        self.errors = errors;
+
+        if world
+            .read_resource::<ArgMatches<'static>>()
+            .is_present("dev")
+        {
+            info!("Watching data and map files for changes.");
+            world.add_resource(AssetWatcher::new());
+        }
 
         let mut title_transform = Transform::default();
         title_transform.translation = Vector3::new(MAP_WIDTH / 2.0, MAP_HEIGHT / 2.0, 0.0);
@@ -129,6 +180,11 @@ impl<'a, 'b> SimpleState<'a, 'b> for Load {
             .with(GlobalTransform::default())
             .build();
         self.entities.push(title_card);
+
+        if !self.errors.is_empty() {
+            self.errors.iter().for_each(|(_, e)| error!("{}", e));
+            self.show_errors(&mut world);
+        }
     }
 
     fn handle_event(
@@ -141,8 +197,9 @@ impl<'a, 'b> SimpleState<'a, 'b> for Load {
         if let StateEvent::Window(event) = &event {
             if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
                 return Trans::Quit;
-            } else if is_key_down(&event, VirtualKeyCode::Return) {
-                return Trans::Switch(Box::new(FreePlay::default()));
+            } else if is_key_down(&event, VirtualKeyCode::Return) && self.errors.is_empty() {
+                let maps = std::mem::replace(&mut self.maps, Vec::new());
+                return Trans::Switch(Box::new(Lobby::with_maps(maps)));
             }
         }
         Trans::None
@@ -159,6 +216,20 @@ impl<'a, 'b> SimpleState<'a, 'b> for Load {
         //super::update_viewport(camera, world);
         //}
 
+        let reloaded = hot_reload(world);
+        if reloaded.data.is_some() || reloaded.palette.is_some() || reloaded.maps.is_some() {
+            self.replace_errors(world, reloaded);
+        }
+
+        if !self.errors.is_empty() {
+            return Trans::None;
+        }
+
+        if let ReplayMode::Playing { replay, .. } = &*world.read_resource::<ReplayMode>() {
+            info!("PLAYING BACK REPLAY WITH SEED {}", replay.seed);
+            return Trans::Switch(Box::new(Game::from_replay(replay)));
+        }
+
         Trans::None
     }
 
@@ -167,5 +238,80 @@ impl<'a, 'b> SimpleState<'a, 'b> for Load {
         world
             .delete_entities(self.entities.as_slice())
             .expect("Failed to clean world of Load's entities!");
+        world
+            .delete_entities(self.error_lines.as_slice())
+            .expect("Failed to clean world of Load's error lines!");
+    }
+}
+
+impl Load {
+    /// Swaps the errors of every source that was hot reloaded for the ones found now, so fixing
+    /// the files with `--dev` lets the game continue without a restart.
+    fn replace_errors(&mut self, world: &mut World, reloaded: Reloaded) {
+        let mut replace = |source, errors: Vec<LoadError>| {
+            self.errors.retain(|(s, _)| *s != source);
+            self.errors.extend(errors.into_iter().map(|e| (source, e)));
+        };
+        if let Some(errors) = reloaded.data {
+            replace(Source::Data, errors);
+        }
+        if let Some(errors) = reloaded.palette {
+            replace(Source::Palette, errors);
+        }
+        if let Some((maps, errors)) = reloaded.maps {
+            replace(Source::Maps, errors);
+            self.maps = maps;
+        }
+        self.show_errors(world);
+    }
+
+    /// Lists every problem found while loading on screen, the game cannot continue past this
+    /// state until they are fixed.
+    fn show_errors(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.error_lines.as_slice())
+            .expect("Failed to clean world of Load's error lines!");
+        self.error_lines.clear();
+
+        let font: FontHandle = world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        );
+
+        for (i, (_, e)) in self.errors.iter().enumerate() {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("load_error_{}", i),
+                    Anchor::TopMiddle,
+                    0.0,
+                    -16.0 - 14.0 * i as f32,
+                    1.0,
+                    VIEW_WIDTH - 16.0,
+                    14.0,
+                    0,
+                ))
+                .with(UiText::new(
+                    font.clone(),
+                    e.to_string(),
+                    [1.0, 0.3, 0.3, 1.0],
+                    12.0,
+                ))
+                .build();
+            self.error_lines.push(entity);
+        }
     }
 }
+
+/// Converts palette entries as stored in `palette.ron` into colors.
+pub(crate) fn palette_colors(
+    palette: Vec<(String, u8, u8, u8, u8)>,
+) -> HashMap<String, image::Rgba<u8>> {
+    palette
+        .into_iter()
+        .map(|x| (x.0, image::Rgba([x.2, x.3, x.4, x.1])))
+        .collect()
+}
@@ -0,0 +1,421 @@
+use super::{freeplay::*, remap::*};
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::prelude::*,
+    input::{is_close_requested, is_key_down, InputHandler},
+    prelude::*,
+    renderer::VirtualKeyCode,
+    shrev::{EventChannel, ReaderId},
+    ui::{Anchor, FontAsset, FontHandle, TtfFormat, UiText, UiTransform},
+};
+use gilrs::ev;
+use log::*;
+use nk_data::*;
+use nk_ecs::*;
+use std::sync::{Arc, Mutex};
+
+pub(crate) const MEMBERS: [TeamMember; 4] = [
+    TeamMember::Captain,
+    TeamMember::Server,
+    TeamMember::ScooperOne,
+    TeamMember::ScooperTwo,
+];
+
+const HELP: [&str; 4] = [
+    "Gamepad: A join, B leave, Select share pad, D-Pad/X side, Up/Y role",
+    "Keyboard: E or Right Shift join, A D/Left Right side, W/Up role",
+    "Tab to change gamepad controls",
+    "Start or Enter to play",
+];
+
+#[derive(Debug)]
+struct Seat {
+    team: usize,
+    player: LobbyPlayer,
+}
+
+/// Lets every gamepad or keyboard half join a side with a role before going to FreePlay.
+pub struct Lobby {
+    maps: Vec<MapDefinition>,
+    seats: Vec<Seat>,
+    reader: Option<ReaderId<ev::Event>>,
+    font: Option<FontHandle>,
+    lines: Vec<Entity>,
+    changed: bool,
+}
+
+impl Lobby {
+    pub fn with_maps(maps: Vec<MapDefinition>) -> Self {
+        Lobby {
+            maps,
+            seats: Vec::with_capacity(8),
+            reader: None,
+            font: None,
+            lines: Vec::with_capacity(16),
+            changed: true,
+        }
+    }
+
+    pub fn config(&self) -> LobbyConfig {
+        let team = |t: usize| {
+            self.seats
+                .iter()
+                .filter(|s| s.team == t)
+                .map(|s| s.player.clone())
+                .collect()
+        };
+        LobbyConfig {
+            left: team(0),
+            right: team(1),
+        }
+    }
+
+    /// Index of the seat taken by `device`, `half` picks between two players sharing it.
+    fn seat(&self, device: InputDevice, half: Style) -> Option<usize> {
+        self.seats.iter().position(|s| {
+            s.player.device == device && (s.player.style == Style::Full || s.player.style == half)
+        })
+    }
+
+    fn free_member(&self, team: usize) -> Option<TeamMember> {
+        MEMBERS
+            .iter()
+            .find(|m| {
+                !self
+                    .seats
+                    .iter()
+                    .any(|s| s.team == team && s.player.member == **m)
+            })
+            .cloned()
+    }
+
+    fn join(&mut self, device: InputDevice, style: Style) {
+        if self.seat(device, style).is_some() {
+            return;
+        }
+        let smaller = if self.seats.iter().filter(|s| s.team == 0).count()
+            <= self.seats.iter().filter(|s| s.team == 1).count()
+        {
+            0
+        } else {
+            1
+        };
+        for team in [smaller, 1 - smaller].iter() {
+            if let Some(member) = self.free_member(*team) {
+                info!("{} joined team {} as {}", device, team, member.key());
+                self.seats.push(Seat {
+                    team: *team,
+                    player: LobbyPlayer {
+                        member,
+                        device,
+                        style,
+                    },
+                });
+                self.changed = true;
+                return;
+            }
+        }
+        warn!("{} cannot join, both teams are full", device);
+    }
+
+    fn leave(&mut self, device: InputDevice, half: Style) {
+        if let Some(i) = self.seat(device, half) {
+            let seat = self.seats.remove(i);
+            info!("{} left team {}", device, seat.team);
+            self.changed = true;
+        }
+    }
+
+    fn switch_side(&mut self, device: InputDevice, half: Style) {
+        if let Some(i) = self.seat(device, half) {
+            let team = 1 - self.seats[i].team;
+            if let Some(member) = self.free_member(team) {
+                self.seats[i].team = team;
+                self.seats[i].player.member = member;
+                self.changed = true;
+            }
+        }
+    }
+
+    fn cycle_role(&mut self, device: InputDevice, half: Style) {
+        if let Some(i) = self.seat(device, half) {
+            let team = self.seats[i].team;
+            let current = MEMBERS
+                .iter()
+                .position(|m| *m == self.seats[i].player.member)
+                .unwrap_or(0);
+            let next = (1..MEMBERS.len())
+                .map(|o| &MEMBERS[(current + o) % MEMBERS.len()])
+                .find(|m| {
+                    !self
+                        .seats
+                        .iter()
+                        .any(|s| s.team == team && s.player.member == **m)
+                })
+                .cloned();
+            if let Some(member) = next {
+                self.seats[i].player.member = member;
+                self.changed = true;
+            }
+        }
+    }
+
+    /// Splits a gamepad between two players or gives it back to the one on its left half.
+    fn toggle_sharing(&mut self, device: InputDevice) {
+        let halves = self
+            .seats
+            .iter()
+            .position(|s| s.player.device == device && s.player.style == Style::HalfRight);
+        if let Some(i) = halves {
+            self.seats.remove(i);
+            if let Some(i) = self.seat(device, Style::HalfLeft) {
+                self.seats[i].player.style = Style::Full;
+            }
+            self.changed = true;
+        } else if let Some(i) = self.seat(device, Style::Full) {
+            self.seats[i].player.style = Style::HalfLeft;
+            self.join(device, Style::HalfRight);
+            self.changed = true;
+        }
+    }
+
+    fn start<'a, 'b>(&mut self) -> SimpleTrans<'a, 'b> {
+        let config = self.config();
+        let errors = config.validate();
+        if !errors.is_empty() {
+            errors.iter().for_each(|e| warn!("cannot start: {}", e));
+            return Trans::None;
+        }
+        let maps = std::mem::replace(&mut self.maps, Vec::new());
+        Trans::Switch(Box::new(FreePlay::with_maps(maps).with_lobby(config)))
+    }
+
+    fn handle_gamepad(&mut self, id: usize, button: ev::Button) -> bool {
+        let device = InputDevice::Gamepad(id);
+        match button {
+            ev::Button::South => self.join(device, Style::Full),
+            ev::Button::East => {
+                self.leave(device, Style::HalfRight);
+                self.leave(device, Style::HalfLeft);
+            }
+            ev::Button::Select => self.toggle_sharing(device),
+            ev::Button::Start => return true,
+            ev::Button::DPadLeft | ev::Button::DPadRight => {
+                self.switch_side(device, Style::HalfLeft)
+            }
+            ev::Button::DPadUp => self.cycle_role(device, Style::HalfLeft),
+            ev::Button::West => self.switch_side(device, Style::HalfRight),
+            ev::Button::North => self.cycle_role(device, Style::HalfRight),
+            _ => {}
+        }
+        false
+    }
+
+    fn refresh(&mut self, world: &mut World) {
+        self.clear(world);
+
+        let font = match &self.font {
+            Some(font) => font.clone(),
+            None => return,
+        };
+
+        let mut lines = HELP
+            .iter()
+            .map(|h| (h.to_string(), [1.0, 1.0, 1.0, 1.0]))
+            .collect::<Vec<_>>();
+        for (team, side) in ["left", "right"].iter().enumerate() {
+            for seat in self.seats.iter().filter(|s| s.team == team) {
+                lines.push((
+                    format!(
+                        "{} {}: {} ({:?})",
+                        side,
+                        seat.player.member.key(),
+                        seat.player.device,
+                        seat.player.style
+                    ),
+                    [1.0, 1.0, 1.0, 1.0],
+                ));
+            }
+        }
+        let errors = self.config().validate();
+        if errors.is_empty() {
+            lines.push(("ready".to_owned(), [0.3, 1.0, 0.3, 1.0]));
+        }
+        lines.extend(errors.iter().map(|e| (e.to_string(), [1.0, 0.3, 0.3, 1.0])));
+
+        self.lines = create_text_lines(world, &font, "lobby", lines);
+    }
+
+    fn clear(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.lines.as_slice())
+            .expect("Failed to delete lobby text!");
+        self.lines.clear();
+    }
+}
+
+/// Lists `lines` from the top of the screen, used by the menus that are only text.
+pub(crate) fn create_text_lines(
+    world: &mut World,
+    font: &FontHandle,
+    id: &str,
+    lines: Vec<(String, [f32; 4])>,
+) -> Vec<Entity> {
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, (text, color))| {
+            world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("{}_line_{}", id, i),
+                    Anchor::TopMiddle,
+                    0.0,
+                    -16.0 - 14.0 * i as f32,
+                    1.0,
+                    VIEW_WIDTH - 16.0,
+                    14.0,
+                    0,
+                ))
+                .with(UiText::new(font.clone(), text, color, 12.0))
+                .build()
+        })
+        .collect()
+}
+
+impl<'a, 'b> SimpleState<'a, 'b> for Lobby {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+        self.reader = Some(
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .register_reader(),
+        );
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<GameData>,
+        event: StateEvent,
+    ) -> SimpleTrans<'a, 'b> {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                return self.start();
+            }
+            if is_key_down(&event, VirtualKeyCode::Tab) {
+                return Trans::Push(Box::new(Remap::new(self.config())));
+            }
+
+            let keys = [
+                (
+                    VirtualKeyCode::E,
+                    VirtualKeyCode::A,
+                    VirtualKeyCode::D,
+                    VirtualKeyCode::W,
+                ),
+                (
+                    VirtualKeyCode::RShift,
+                    VirtualKeyCode::Left,
+                    VirtualKeyCode::Right,
+                    VirtualKeyCode::Up,
+                ),
+            ];
+            let halves = [Style::HalfLeft, Style::HalfRight];
+            for ((join, left, right, role), half) in keys.iter().zip(halves.iter()) {
+                if is_key_down(&event, *join) {
+                    if self.seat(InputDevice::Keyboard, *half).is_some() {
+                        self.leave(InputDevice::Keyboard, *half);
+                    } else if keyboard_bound(
+                        &data.world.read_resource::<InputHandler<String, String>>(),
+                        *half,
+                    ) {
+                        self.join(InputDevice::Keyboard, *half);
+                    } else {
+                        // Such a seat would never move, KeyboardSystem has nothing to read.
+                        warn!("{:?} keyboard has no bindings in resources/input.ron", half);
+                    }
+                } else if is_key_down(&event, *left) || is_key_down(&event, *right) {
+                    self.switch_side(InputDevice::Keyboard, *half);
+                } else if is_key_down(&event, *role) {
+                    self.cycle_role(InputDevice::Keyboard, *half);
+                }
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans<'a, 'b> {
+        let events = match &mut self.reader {
+            Some(reader) => data
+                .world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .read(reader)
+                .map(|e| (e.id, e.event.clone()))
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        let mut start = false;
+        for (id, event) in events {
+            match event {
+                ev::EventType::ButtonPressed(button, _) => {
+                    start |= self.handle_gamepad(id, button);
+                }
+                ev::EventType::Disconnected => {
+                    self.leave(InputDevice::Gamepad(id), Style::HalfRight);
+                    self.leave(InputDevice::Gamepad(id), Style::HalfLeft);
+                }
+                _ => {}
+            }
+        }
+
+        if self.changed {
+            self.changed = false;
+            self.refresh(&mut data.world);
+        }
+
+        if start {
+            return self.start();
+        }
+        Trans::None
+    }
+
+    fn on_pause(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        self.clear(world);
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        // Buttons pressed while remapping are not meant for the lobby.
+        if let Some(reader) = &mut self.reader {
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .read(reader)
+                .for_each(|_| {});
+        }
+        self.changed = true;
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        self.clear(world);
+    }
+}
@@ -0,0 +1,202 @@
+use super::lobby::*;
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::prelude::*,
+    input::{is_close_requested, is_key_down, InputHandler},
+    prelude::*,
+    renderer::VirtualKeyCode,
+    shrev::{EventChannel, ReaderId},
+    ui::{FontAsset, FontHandle, TtfFormat},
+};
+use gilrs::ev;
+use nk_data::*;
+use nk_ecs::*;
+use std::sync::{Arc, Mutex};
+
+/// What the paused match should do once the pause menu is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseChoice {
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseChoice {
+    fn label(&self) -> &'static str {
+        match self {
+            PauseChoice::Resume => "Resume",
+            PauseChoice::Restart => "Restart",
+            PauseChoice::Quit => "Quit to menu",
+        }
+    }
+}
+
+impl Default for PauseChoice {
+    fn default() -> Self {
+        PauseChoice::Resume
+    }
+}
+
+const CHOICES: [PauseChoice; 3] = [PauseChoice::Resume, PauseChoice::Restart, PauseChoice::Quit];
+
+/// Pushed on top of `Game`, which stops stepping the match until this state is popped. The
+/// chosen option is left in the `PauseChoice` resource for `Game` to act on.
+pub struct Paused {
+    selected: usize,
+    reader: Option<ReaderId<ev::Event>>,
+    font: Option<FontHandle>,
+    lines: Vec<Entity>,
+    changed: bool,
+    pause_held: bool,
+}
+
+impl Paused {
+    pub fn new() -> Self {
+        Paused {
+            selected: 0,
+            reader: None,
+            font: None,
+            lines: Vec::with_capacity(8),
+            changed: true,
+            // The key that paused the match is likely still down, it has to be released first.
+            pause_held: true,
+        }
+    }
+
+    fn select(&mut self, offset: usize) {
+        self.selected = (self.selected + offset) % CHOICES.len();
+        self.changed = true;
+    }
+
+    fn close<'a, 'b>(&mut self, world: &mut World, choice: PauseChoice) -> SimpleTrans<'a, 'b> {
+        *world.write_resource::<PauseChoice>() = choice;
+        Trans::Pop
+    }
+
+    fn refresh(&mut self, world: &mut World) {
+        self.clear(world);
+
+        let font = match &self.font {
+            Some(font) => font.clone(),
+            None => return,
+        };
+
+        let mut lines = vec![("Paused".to_owned(), [1.0, 1.0, 1.0, 1.0])];
+        lines.extend(CHOICES.iter().enumerate().map(|(i, c)| {
+            if i == self.selected {
+                (format!("> {}", c.label()), [1.0, 0.85, 0.2, 1.0])
+            } else {
+                (c.label().to_owned(), [1.0, 1.0, 1.0, 1.0])
+            }
+        }));
+        self.lines = create_text_lines(world, &font, "paused", lines);
+    }
+
+    fn clear(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.lines.as_slice())
+            .expect("Failed to delete pause menu text!");
+        self.lines.clear();
+    }
+}
+
+impl<'a, 'b> SimpleState<'a, 'b> for Paused {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+        self.reader = Some(
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .register_reader(),
+        );
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<GameData>,
+        event: StateEvent,
+    ) -> SimpleTrans<'a, 'b> {
+        let StateData { world, .. } = data;
+
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return self.close(world, PauseChoice::Resume);
+            }
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                return self.close(world, CHOICES[self.selected]);
+            }
+            if is_key_down(&event, VirtualKeyCode::Up) {
+                self.select(CHOICES.len() - 1);
+            } else if is_key_down(&event, VirtualKeyCode::Down) {
+                self.select(1);
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans<'a, 'b> {
+        let pressed = match &mut self.reader {
+            Some(reader) => data
+                .world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .read(reader)
+                .filter_map(|e| match &e.event {
+                    ev::EventType::ButtonPressed(b, _) => Some(*b),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        let held = {
+            let handler = data.world.read_resource::<InputHandler<String, String>>();
+            [Style::HalfLeft, Style::HalfRight]
+                .iter()
+                .any(|s| keyboard_pause_down(&handler, *s))
+        };
+        if held && !self.pause_held {
+            self.pause_held = held;
+            return self.close(&mut data.world, PauseChoice::Resume);
+        }
+        self.pause_held = held;
+
+        for button in pressed {
+            match button {
+                ev::Button::Start | ev::Button::East => {
+                    return self.close(&mut data.world, PauseChoice::Resume);
+                }
+                ev::Button::South => {
+                    return self.close(&mut data.world, CHOICES[self.selected]);
+                }
+                ev::Button::DPadUp => self.select(CHOICES.len() - 1),
+                ev::Button::DPadDown => self.select(1),
+                _ => {}
+            }
+        }
+
+        if self.changed {
+            self.changed = false;
+            self.refresh(&mut data.world);
+        }
+        Trans::None
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        self.clear(world);
+    }
+}
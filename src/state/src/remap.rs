@@ -0,0 +1,257 @@
+use super::lobby::*;
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::prelude::*,
+    input::{is_close_requested, is_key_down},
+    prelude::*,
+    renderer::VirtualKeyCode,
+    shrev::{EventChannel, ReaderId},
+    ui::{FontAsset, FontHandle, TtfFormat},
+};
+use gilrs::ev;
+use nk_data::*;
+use nk_ecs::*;
+use nk_loader::*;
+use std::sync::{Arc, Mutex};
+
+const HELP: [&str; 2] = [
+    "Up/Down player, Enter bind buttons, S stick, X/Y invert, +/- dead zone, R reset",
+    "Escape to save and go back",
+];
+
+/// Edits the gamepad binding profile of each player and saves them to the user controls.
+pub struct Remap {
+    slots: Vec<(String, Style)>,
+    selected: usize,
+    capture: Option<usize>,
+    reader: Option<ReaderId<ev::Event>>,
+    font: Option<FontHandle>,
+    lines: Vec<Entity>,
+    changed: bool,
+}
+
+impl Remap {
+    /// Players without a profile show the defaults for the style they picked in `lobby`.
+    pub fn new(lobby: LobbyConfig) -> Self {
+        let mut slots = Vec::with_capacity(8);
+        for (side, players) in lobby.sides().iter() {
+            for member in MEMBERS.iter() {
+                let style = players
+                    .iter()
+                    .find(|p| p.member == *member)
+                    .map(|p| p.style)
+                    .unwrap_or(Style::Full);
+                slots.push((LobbyConfig::palette_key(side, member), style));
+            }
+        }
+        Remap {
+            slots,
+            selected: 0,
+            capture: None,
+            reader: None,
+            font: None,
+            lines: Vec::with_capacity(16),
+            changed: true,
+        }
+    }
+
+    fn edit<F: FnOnce(&mut BindingProfile)>(&mut self, world: &mut World, f: F) {
+        let (key, style) = &self.slots[self.selected];
+        let mut controls = world.write_resource::<ControlsConfig>();
+        let mut profile = controls.profile(key, *style);
+        f(&mut profile);
+        controls.set_profile(key, *style, profile);
+        self.changed = true;
+    }
+
+    fn refresh(&mut self, world: &mut World) {
+        world
+            .delete_entities(self.lines.as_slice())
+            .expect("Failed to delete remap text!");
+        self.lines.clear();
+
+        let font = match &self.font {
+            Some(font) => font.clone(),
+            None => return,
+        };
+
+        let white = [1.0, 1.0, 1.0, 1.0];
+        let (key, style) = &self.slots[self.selected];
+        let (profile, custom) = {
+            let controls = world.read_resource::<ControlsConfig>();
+            (
+                controls.profile(key, *style),
+                controls.has_profile(key, *style),
+            )
+        };
+
+        let mut lines = HELP
+            .iter()
+            .map(|h| (h.to_string(), white))
+            .collect::<Vec<_>>();
+        lines.push((
+            format!(
+                "< {} ({:?}) > {}",
+                key,
+                style,
+                if custom { "custom" } else { "default" }
+            ),
+            [1.0, 1.0, 0.3, 1.0],
+        ));
+        for (i, action) in GameAction::all().iter().enumerate() {
+            let color = if self.capture == Some(i) {
+                [0.3, 1.0, 0.3, 1.0]
+            } else {
+                white
+            };
+            lines.push((
+                format!("{:?}: {:?}", action, profile.buttons_for(*action)),
+                color,
+            ));
+        }
+        lines.push((
+            format!(
+                "stick {:?}, dead zone {:.2}, invert x {}, invert y {}",
+                profile.stick, profile.dead_zone, profile.invert_x, profile.invert_y
+            ),
+            white,
+        ));
+        if let Some(i) = self.capture {
+            lines.push((
+                format!(
+                    "press a gamepad button for {:?}, Escape to stop",
+                    GameAction::all()[i]
+                ),
+                [0.3, 1.0, 0.3, 1.0],
+            ));
+        }
+
+        self.lines = create_text_lines(world, &font, "remap", lines);
+    }
+}
+
+impl<'a, 'b> SimpleState<'a, 'b> for Remap {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+        self.reader = Some(
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .register_reader(),
+        );
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<GameData>,
+        event: StateEvent,
+    ) -> SimpleTrans<'a, 'b> {
+        let StateData { world, .. } = data;
+
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                if self.capture.is_some() {
+                    self.capture = None;
+                    self.changed = true;
+                } else {
+                    save_controls(&world.read_resource::<ControlsConfig>());
+                    return Trans::Pop;
+                }
+            }
+            if self.capture.is_some() {
+                return Trans::None;
+            }
+
+            if is_key_down(&event, VirtualKeyCode::Up) {
+                self.selected = (self.selected + self.slots.len() - 1) % self.slots.len();
+                self.changed = true;
+            } else if is_key_down(&event, VirtualKeyCode::Down) {
+                self.selected = (self.selected + 1) % self.slots.len();
+                self.changed = true;
+            } else if is_key_down(&event, VirtualKeyCode::Return) {
+                self.capture = Some(0);
+                self.changed = true;
+            } else if is_key_down(&event, VirtualKeyCode::S) {
+                self.edit(world, |p| {
+                    p.stick = match p.stick {
+                        PadStick::Left => PadStick::Right,
+                        PadStick::Right => PadStick::Left,
+                    }
+                });
+            } else if is_key_down(&event, VirtualKeyCode::X) {
+                self.edit(world, |p| p.invert_x = !p.invert_x);
+            } else if is_key_down(&event, VirtualKeyCode::Y) {
+                self.edit(world, |p| p.invert_y = !p.invert_y);
+            } else if is_key_down(&event, VirtualKeyCode::Equals)
+                || is_key_down(&event, VirtualKeyCode::Add)
+            {
+                self.edit(world, |p| p.dead_zone = (p.dead_zone + 0.05).min(0.9));
+            } else if is_key_down(&event, VirtualKeyCode::Minus)
+                || is_key_down(&event, VirtualKeyCode::Subtract)
+            {
+                self.edit(world, |p| p.dead_zone = (p.dead_zone - 0.05).max(0.0));
+            } else if is_key_down(&event, VirtualKeyCode::R) {
+                let (key, style) = &self.slots[self.selected];
+                world
+                    .write_resource::<ControlsConfig>()
+                    .reset_profile(key, *style);
+                self.changed = true;
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans<'a, 'b> {
+        let pressed = match &mut self.reader {
+            Some(reader) => data
+                .world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .read(reader)
+                .filter_map(|e| match &e.event {
+                    ev::EventType::ButtonPressed(b, _) => pad_button(*b),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        for button in pressed {
+            if let Some(i) = self.capture {
+                let action = GameAction::all()[i];
+                self.edit(&mut data.world, |p| p.bind(action, button));
+                self.capture = if i + 1 < GameAction::all().len() {
+                    Some(i + 1)
+                } else {
+                    None
+                };
+            }
+        }
+
+        if self.changed {
+            self.changed = false;
+            self.refresh(&mut data.world);
+        }
+        Trans::None
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        world
+            .delete_entities(self.lines.as_slice())
+            .expect("Failed to clean world of Remap's entities!");
+    }
+}
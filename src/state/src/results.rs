@@ -0,0 +1,222 @@
+use super::{freeplay::*, game::*, lobby::*};
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::prelude::*,
+    input::{is_close_requested, is_key_down},
+    prelude::*,
+    renderer::VirtualKeyCode,
+    shrev::{EventChannel, ReaderId},
+    ui::{FontAsset, FontHandle, TtfFormat},
+};
+use gilrs::ev;
+use nk_data::*;
+use nk_ecs::*;
+use nk_loader::*;
+use std::sync::{Arc, Mutex};
+
+const SIDES: [&str; 2] = ["left", "right"];
+
+const HELP: [&str; 2] = [
+    "Enter or Start to play a rematch",
+    "Escape or B to go back to FreePlay",
+];
+
+/// Collects the scores of both teams and the stats of every player of the match in `world`.
+pub fn match_results(world: &World) -> MatchResults {
+    let scores = world
+        .read_resource::<Match>()
+        .teams
+        .iter()
+        .map(|t| t.score)
+        .collect();
+    let stats = world.read_resource::<MatchStats>();
+    let players = (&*world.entities(), &world.read_storage::<Player>())
+        .join()
+        .map(|(e, p)| PlayerResult {
+            team: p.team_index,
+            key: p.palette_key.clone(),
+            stats: stats.player(e),
+        })
+        .collect();
+    MatchResults {
+        scores,
+        players,
+        teams: stats.teams.clone(),
+    }
+}
+
+/// Results of the match in `world` along with what is needed to tell matches apart.
+pub fn match_report(world: &World) -> MatchReport {
+    let results = match_results(world);
+    let match_data = world.read_resource::<Match>();
+    let team = match_data.teams.first();
+    MatchReport {
+        seed: world.read_resource::<MatchRng>().seed(),
+        generation: match_data.generation.key.clone(),
+        flavors: team.map(|t| t.flavors.clone()).unwrap_or_default(),
+        preparations: team.map(|t| t.preparations.clone()).unwrap_or_default(),
+        toppings: team.map(|t| t.toppings.clone()).unwrap_or_default(),
+        hissatsu: match_data
+            .teams
+            .iter()
+            .map(|t| t.hissatsu.clone())
+            .collect(),
+        duration: match_data.elapsed,
+        winner: results.winner(),
+        scores: results.scores,
+        players: results.players,
+        teams: results.teams,
+    }
+}
+
+/// Shows the winner and what everyone did once the timer runs out.
+pub struct Results {
+    results: MatchResults,
+    rematch: Option<Game>,
+    lobby: LobbyConfig,
+    reader: Option<ReaderId<ev::Event>>,
+    font: Option<FontHandle>,
+    lines: Vec<Entity>,
+}
+
+impl Results {
+    /// `rematch` is started as is when players ask for one, `lobby` is kept for FreePlay.
+    pub fn new(results: MatchResults, rematch: Game, lobby: LobbyConfig) -> Self {
+        Results {
+            results,
+            rematch: Some(rematch),
+            lobby,
+            reader: None,
+            font: None,
+            lines: Vec::with_capacity(16),
+        }
+    }
+
+    fn rematch<'a, 'b>(&mut self) -> SimpleTrans<'a, 'b> {
+        match self.rematch.take() {
+            Some(game) => Trans::Switch(Box::new(game)),
+            None => Trans::None,
+        }
+    }
+
+    fn back<'a, 'b>(&mut self) -> SimpleTrans<'a, 'b> {
+        let lobby = self.lobby.clone();
+        Trans::Switch(Box::new(FreePlay::default().with_lobby(lobby)))
+    }
+
+    fn refresh(&mut self, world: &mut World) {
+        let font = match &self.font {
+            Some(font) => font.clone(),
+            None => return,
+        };
+
+        let white = [1.0, 1.0, 1.0, 1.0];
+        let gold = [1.0, 0.85, 0.2, 1.0];
+        let mut lines = Vec::with_capacity(16);
+        lines.push(match self.results.winner() {
+            Some(team) => (format!("{} team wins!", SIDES[team % 2]), gold),
+            None => ("Draw!".to_owned(), gold),
+        });
+        if self.results.scores.len() == 2 {
+            lines.push((
+                format!(
+                    "left {} - {} right",
+                    self.results.scores[0], self.results.scores[1]
+                ),
+                white,
+            ));
+        }
+
+        let mvp = self.results.mvp().map(|p| p.key.clone());
+        for player in self.results.players.iter() {
+            let stats = &player.stats;
+            let text = format!(
+                "{}: {} points, {} deliveries, {} scoops, {} toppings",
+                player.key, stats.points, stats.deliveries, stats.scoops, stats.toppings
+            );
+            if mvp.as_ref() == Some(&player.key) {
+                lines.push((format!("{} (MVP)", text), gold));
+            } else {
+                lines.push((text, white));
+            }
+        }
+        lines.extend(HELP.iter().map(|h| (h.to_string(), white)));
+
+        self.lines = create_text_lines(world, &font, "results", lines);
+    }
+}
+
+impl<'a, 'b> SimpleState<'a, 'b> for Results {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+
+        self.font = Some(world.read_resource::<Loader>().load(
+            "font/kenney-high-square.ttf",
+            TtfFormat,
+            (),
+            (),
+            &world.read_resource::<AssetStorage<FontAsset>>(),
+        ));
+        self.reader = Some(
+            world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .register_reader(),
+        );
+        self.refresh(world);
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<GameData>,
+        event: StateEvent,
+    ) -> SimpleTrans<'a, 'b> {
+        if let StateEvent::Window(event) = &event {
+            if is_close_requested(&event) || is_key_down(&event, VirtualKeyCode::Q) {
+                return Trans::Quit;
+            }
+            if is_key_down(&event, VirtualKeyCode::Return) {
+                return self.rematch();
+            }
+            if is_key_down(&event, VirtualKeyCode::Escape) {
+                return self.back();
+            }
+        }
+        Trans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans<'a, 'b> {
+        let pressed = match &mut self.reader {
+            Some(reader) => data
+                .world
+                .read_resource::<Arc<Mutex<EventChannel<ev::Event>>>>()
+                .lock()
+                .unwrap()
+                .read(reader)
+                .filter_map(|e| match &e.event {
+                    ev::EventType::ButtonPressed(b, _) => Some(*b),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        for button in pressed {
+            match button {
+                ev::Button::Start => return self.rematch(),
+                ev::Button::East => return self.back(),
+                _ => {}
+            }
+        }
+        Trans::None
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let StateData { world, .. } = data;
+        world
+            .delete_entities(self.lines.as_slice())
+            .expect("Failed to delete results text!");
+        self.lines.clear();
+    }
+}
@@ -0,0 +1,160 @@
+use amethyst::{
+    core::{
+        cgmath::*,
+        transform::{GlobalTransform, Parent, Transform},
+    },
+    ecs::prelude::*,
+};
+use either::*;
+use log::*;
+use nalgebra::Vector2 as NAVector2;
+use ncollide2d::shape::*;
+use nk_data::*;
+use nk_ecs::*;
+use nk_util::*;
+
+/// Everything a match is played with. Builds the kitchens, players and `Match` resource shared by
+/// the `Game` state and headless `Simulation`s, `Game` adds rendering on top.
+pub(crate) struct MatchSetup<'s> {
+    pub map: &'s MapDefinition,
+    pub flavors: &'s [FlavorIndex],
+    pub preparations: &'s [PreparationIndex],
+    pub toppings: &'s [ToppingIndex],
+    /// Overrides the generation profile chosen by the map.
+    pub generation: Option<&'s String>,
+    pub hissatsu: &'s [Option<HissatsuIndex>],
+    pub lobby: &'s LobbyConfig,
+    pub duration: f32,
+}
+
+impl<'s> MatchSetup<'s> {
+    /// Parents of the left and right kitchens, the right one is mirrored, and the walls around
+    /// both kitchens.
+    pub fn create_kitchens(world: &mut World) -> ((Entity, Entity), Vec<Entity>) {
+        let mut left_transform = Transform::default();
+        left_transform.translation = Vector3::new(MAP_OFFSET_X, MAP_OFFSET_Y, 0.0);
+        let left = world
+            .create_entity()
+            .with(left_transform)
+            .with(GlobalTransform::default())
+            .build();
+
+        let mut right_transform = Transform::default();
+        right_transform.translation = Vector3::new(MAP_WIDTH - MAP_OFFSET_X, MAP_OFFSET_Y, 0.0);
+        right_transform.scale = Vector3::new(-1.0, 1.0, 1.0);
+        let right = world
+            .create_entity()
+            .with(right_transform)
+            .with(GlobalTransform::default())
+            .build();
+
+        let walls = create_kitchen_bounds(
+            world,
+            (left, right),
+            (
+                0.0,
+                0.0,
+                BASE * KITCHEN_WIDTH as f32,
+                BASE * KITCHEN_HEIGHT as f32,
+            ),
+        );
+        ((left, right), walls)
+    }
+
+    /// Creates the players of the lobby at their spawn points and adds the `Match` resource with
+    /// their teams. `decorate` is called with every new player, returns the players in lobby order.
+    pub fn create_match(
+        &self,
+        world: &mut World,
+        (left_parent, right_parent): (Entity, Entity),
+        spawn_points: &[(f32, f32)],
+        mut decorate: impl FnMut(&mut World, Entity),
+    ) -> Vec<Entity> {
+        let mut data = Match::default();
+        data.timer = self.duration;
+        data.generation = world.read_resource::<Definitions>().generation_profile(
+            self.generation
+                .or(self.map.generation.as_ref())
+                .map(|g| g.as_str()),
+        );
+        data.order_gen_timer = data.generation.initial_delay;
+        info!("using generation profile {}", data.generation.key);
+
+        let mut entities = Vec::with_capacity(8);
+        let parents = [left_parent, right_parent];
+        for (team_index, (side, players)) in self.lobby.sides().iter().enumerate() {
+            let members = players
+                .iter()
+                .map(|p| {
+                    let entity = create_player(
+                        world,
+                        team_index,
+                        parents[team_index],
+                        spawn_points[p.member.spawn()],
+                        p.device,
+                        p.style,
+                        team_index == 1,
+                        &LobbyConfig::palette_key(side, &p.member),
+                    );
+                    decorate(world, entity);
+                    entities.push(entity);
+                    (p.member.clone(), entity)
+                })
+                .collect::<Vec<_>>();
+            data.teams.push(Team {
+                flavors: self.flavors.to_vec(),
+                toppings: self.toppings.to_vec(),
+                preparations: self.preparations.to_vec(),
+                hissatsu: self.hissatsu.get(team_index).cloned().unwrap_or(None),
+                ..Team::new(&members, parents[team_index])
+            });
+        }
+
+        world.add_resource(data);
+        entities
+    }
+}
+
+fn create_player(
+    world: &mut World,
+    team_index: usize,
+    parent: Entity,
+    (x, y): (f32, f32),
+    device: InputDevice,
+    gamepad_style: Style,
+    invert_x_axis: bool,
+    key: &str,
+) -> Entity {
+    let mut transform = Transform::default();
+    transform.translation = Vector3::new(x, y, 0.0);
+
+    world
+        .create_entity()
+        .with(Direction {
+            current: Cardinal::South,
+            previous: None,
+            current_anim: "idle".to_string(),
+        })
+        .with(Player::new_with_style(
+            device,
+            gamepad_style,
+            team_index,
+            0.0,
+            invert_x_axis,
+            key.to_owned(),
+        ))
+        .with(Input::new())
+        .with(Statuses::default())
+        .with(Velocity::new(80.0))
+        .with(Hitbox {
+            shape: Either::Left(Cuboid::new(NAVector2::new(
+                PLAYER_HITBOX_WIDTH / 2.0,
+                PLAYER_HITBOX_HEIGHT / 2.0,
+            ))),
+            offset: NAVector2::new(0.0, 0.0),
+        })
+        .with(transform)
+        .with(Parent { entity: parent })
+        .with(GlobalTransform::default())
+        .build()
+}
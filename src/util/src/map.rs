@@ -5,7 +5,6 @@ use amethyst::{
     },
     ecs::prelude::*,
     renderer::{SpriteRender, SpriteSheetHandle},
-    utils::application_root_dir,
 };
 use either::*;
 use log::*;
@@ -13,38 +12,6 @@ use nalgebra::Vector2 as NAVector2;
 use ncollide2d::shape::*;
 use nk_data::*;
 use nk_ecs::*;
-use ron::de::from_reader;
-use std::fs::File;
-
-pub fn load_freeplay_defs() -> Vec<MapDefinition> {
-    let app_root = application_root_dir();
-    let path = format!("{}/assets/map/freeplay.ron", app_root);
-    let f = File::open(&path).expect("Failed opening file");
-    let files: Vec<String> = match from_reader(f) {
-        Ok(x) => x,
-        Err(e) => {
-            error!("Error parsing freeplay collection file: {}", e);
-            return Vec::new();
-        }
-    };
-
-    files
-        .into_iter()
-        .filter_map(|f| {
-            let f = File::open(format!("{}/assets/map/{}.ron", app_root, f))
-                .expect("Failed opening file");
-            let map_def: MapDefinition = match from_reader(f) {
-                Ok(x) => x,
-                Err(e) => {
-                    error!("Error parsing map file: {}", e);
-                    return None;
-                }
-            };
-
-            Some(map_def)
-        })
-        .collect()
-}
 
 pub fn create_map_preview(world: &mut World, map: &MapDefinition) -> Entity {
     let (
@@ -224,45 +191,147 @@ pub fn create_map_from_file(
                     acc
                 });
 
-            let all: Vec<(Entity, Entity)> = map_def
-                .tables
-                .iter()
-                .map(|(x, y, t, o)| {
-                    create_table(
-                        world,
-                        *parent,
-                        flavors,
-                        preparations,
-                        toppings,
-                        t,
-                        o,
-                        *x * BASE,
-                        *y * BASE,
-                    )
-                })
-                .collect();
-
-            let mut tops: Vec<Entity> = all.iter().map(|(top, _side)| *top).collect();
+            tiles.append(&mut create_tables(
+                world,
+                *parent,
+                map_def,
+                flavors,
+                preparations,
+                toppings,
+                true,
+            ));
+            tiles
+        })
+        .fold(Vec::new(), |mut acc, v| {
+            acc.extend(v);
+            acc
+        });
 
-            let mut sides: Vec<Entity> = all.iter().map(|(_top, side)| *side).collect();
+    (tiles, make_spawn_points(map_def))
+}
 
-            tiles.append(&mut tops);
-            tiles.append(&mut sides);
-            tiles
+/// Same as `create_map_from_file` but only creates the gameplay side of the tables, without floor
+/// tiles or sprites, so it can be used by a world that has no renderer.
+pub fn create_map_headless(
+    world: &mut World,
+    (left_parent, right_parent): (Entity, Entity),
+    map_def: &MapDefinition,
+    flavors: &[FlavorIndex],
+    preparations: &[PreparationIndex],
+    toppings: &[ToppingIndex],
+) -> (Vec<Entity>, Vec<(f32, f32)>) {
+    let tables = vec![left_parent, right_parent]
+        .iter()
+        .map(|parent| {
+            create_tables(
+                world,
+                *parent,
+                map_def,
+                flavors,
+                preparations,
+                toppings,
+                false,
+            )
         })
         .fold(Vec::new(), |mut acc, v| {
             acc.extend(v);
             acc
         });
 
-    (
-        tiles,
-        map_def
-            .spawns
-            .iter()
-            .map(|(x, y)| (*x * BASE, *y * BASE))
-            .collect(),
-    )
+    (tables, make_spawn_points(map_def))
+}
+
+fn make_spawn_points(map_def: &MapDefinition) -> Vec<(f32, f32)> {
+    map_def
+        .spawns
+        .iter()
+        .map(|(x, y)| (*x * BASE, *y * BASE))
+        .collect()
+}
+
+fn create_tables(
+    world: &mut World,
+    parent: Entity,
+    map_def: &MapDefinition,
+    flavors: &[FlavorIndex],
+    preparations: &[PreparationIndex],
+    toppings: &[ToppingIndex],
+    render: bool,
+) -> Vec<Entity> {
+    let all: Vec<(Entity, Entity)> = map_def
+        .tables
+        .iter()
+        .map(|(x, y, t, o)| {
+            create_table(
+                world,
+                parent,
+                flavors,
+                preparations,
+                toppings,
+                t,
+                o,
+                *x * BASE,
+                *y * BASE,
+                render,
+            )
+        })
+        .collect();
+
+    let mut tops: Vec<Entity> = all.iter().map(|(top, _side)| *top).collect();
+    let mut sides: Vec<Entity> = all.iter().map(|(_top, side)| *side).collect();
+
+    tops.append(&mut sides);
+    tops
+}
+
+/// Creates the four solid walls around a kitchen, for both sides of the map.
+pub fn create_kitchen_bounds(
+    world: &mut World,
+    (left_parent, right_parent): (Entity, Entity),
+    (x, y, width, height): (f32, f32, f32, f32),
+) -> Vec<Entity> {
+    let xs = vec![x + width / 2.0, x + width / 2.0, x - BASE, x + width + BASE];
+    let ys = vec![
+        y - BASE,
+        y + height + BASE,
+        y + height / 2.0,
+        y + height / 2.0,
+    ];
+    let ws = vec![
+        width + BASE * 2.0,
+        width + BASE * 2.0,
+        BASE * 2.0,
+        BASE * 2.0,
+    ];
+    let hs = vec![
+        BASE * 2.0,
+        BASE * 2.0,
+        height + BASE * 2.0,
+        height + BASE * 2.0,
+    ];
+
+    let mut entities = Vec::with_capacity(8);
+    for (((x, y), w), h) in xs.iter().zip(ys).zip(ws).zip(hs) {
+        let mut transform = Transform::default();
+        transform.translation = Vector3::new(*x, y, 0.0);
+
+        for parent in vec![left_parent, right_parent] {
+            entities.push(
+                world
+                    .create_entity()
+                    .with(Solid)
+                    .with(Hitbox {
+                        shape: Either::Left(Cuboid::new(NAVector2::new(w / 2.0, h / 2.0))),
+                        offset: NAVector2::new(0.0, 0.0),
+                    })
+                    .with(transform.clone())
+                    .with(Parent { entity: parent })
+                    .with(GlobalTransform::default())
+                    .build(),
+            );
+        }
+    }
+    entities
 }
 
 fn create_table(
@@ -275,6 +344,7 @@ fn create_table(
     o: &TableOrientation,
     x: f32,
     y: f32,
+    render: bool,
 ) -> (Entity, Entity) {
     let hitbox = |half_w, half_h| Hitbox {
         shape: Either::Left(Cuboid::new(NAVector2::new(
@@ -316,6 +386,7 @@ fn create_table(
             (String::from("empty"), o.make_orientation_string()),
             Some(Table::new_empty_table()),
             false,
+            render,
         )
     } else {
         let (w, h) = o.make_dim(BASE * 2.0, BASE);
@@ -327,19 +398,16 @@ fn create_table(
 
         match t {
             TableType::Flavor(f) => {
-                let (key, table) = if let Some(my_flavor) = flavors.get(f.0) {
-                    (
-                        world
-                            .read_resource::<Definitions>()
-                            .flavors()
-                            .find(|x| x.index == *my_flavor)
-                            .unwrap()
-                            .key
-                            .clone(),
-                        Some(Table::new_flavor_table(my_flavor.clone())),
-                    )
-                } else {
-                    ("vanilla".to_owned(), None)
+                let (key, table) = match flavors.get(f.0).and_then(|my_flavor| {
+                    world
+                        .read_resource::<Definitions>()
+                        .get_flavor(my_flavor)
+                        .map(|x| (x.key.clone(), my_flavor))
+                }) {
+                    Some((key, my_flavor)) => {
+                        (key, Some(Table::new_flavor_table(my_flavor.clone())))
+                    }
+                    None => ("vanilla".to_owned(), None),
                 };
                 create_entities(
                     world,
@@ -350,22 +418,21 @@ fn create_table(
                     (format!("flavor_{}", key), o.make_orientation_string()),
                     table,
                     true,
+                    render,
                 )
             }
             TableType::Preparation(p) => {
-                let (key, table) = if let Some(my_preparation) = preparations.get(p.0) {
-                    (
-                        world
-                            .read_resource::<Definitions>()
-                            .preparations()
-                            .find(|x| x.index == *my_preparation)
-                            .unwrap()
-                            .key
-                            .clone(),
+                let (key, table) = match preparations.get(p.0).and_then(|my_preparation| {
+                    world
+                        .read_resource::<Definitions>()
+                        .get_preparation(my_preparation)
+                        .map(|x| (x.key.clone(), my_preparation))
+                }) {
+                    Some((key, my_preparation)) => (
+                        key,
                         Some(Table::new_preparation_table(my_preparation.clone())),
-                    )
-                } else {
-                    ("cake_cone".to_owned(), None)
+                    ),
+                    None => ("cake_cone".to_owned(), None),
                 };
                 create_entities(
                     world,
@@ -376,22 +443,20 @@ fn create_table(
                     (format!("preparation_{}", key), o.make_orientation_string()),
                     table,
                     true,
+                    render,
                 )
             }
             TableType::Topping(t) => {
-                let (key, table) = if let Some(my_topping) = toppings.get(t.0) {
-                    (
-                        world
-                            .read_resource::<Definitions>()
-                            .toppings()
-                            .find(|x| x.index == *my_topping)
-                            .unwrap()
-                            .key
-                            .clone(),
-                        Some(Table::new_topping_table(my_topping.clone())),
-                    )
-                } else {
-                    ("sprinkles".to_owned(), None)
+                let (key, table) = match toppings.get(t.0).and_then(|my_topping| {
+                    world
+                        .read_resource::<Definitions>()
+                        .get_topping(my_topping)
+                        .map(|x| (x.key.clone(), my_topping))
+                }) {
+                    Some((key, my_topping)) => {
+                        (key, Some(Table::new_topping_table(my_topping.clone())))
+                    }
+                    None => ("sprinkles".to_owned(), None),
                 };
                 create_entities(
                     world,
@@ -402,6 +467,7 @@ fn create_table(
                     (format!("topping_{}", key), o.make_orientation_string()),
                     table,
                     true,
+                    render,
                 )
             }
             TableType::Delivery => create_entities(
@@ -413,6 +479,7 @@ fn create_table(
                 (String::from("delivery"), o.make_orientation_string()),
                 Some(Table::new_delivery_table()),
                 true,
+                render,
             ),
 
             _ => panic!("Impossible!"),
@@ -429,55 +496,63 @@ fn create_entities(
     (key, orientation): (String, String),
     table: Option<Table>,
     animate: bool,
+    render: bool,
 ) -> (Entity, Entity) {
-    let empty_handle = {
+    let handles = if render {
         let handles = world.read_resource::<Handles>();
-        handles.empty_handle.clone()
-    };
-
-    let map_handle = {
-        let handles = world.read_resource::<Handles>();
-        handles.map_handle.clone()
+        Some((handles.empty_handle.clone(), handles.map_handle.clone()))
+    } else {
+        None
     };
 
     let top = world
         .create_entity()
-        .with(SpriteRender {
-            sprite_sheet: empty_handle,
+        .with(top)
+        .with(Parent { entity: parent })
+        .with(GlobalTransform::default());
+    let top = if let Some(c) = table {
+        top.with(c)
+    } else {
+        top
+    };
+    let top = if let Some((empty_handle, _)) = &handles {
+        top.with(SpriteRender {
+            sprite_sheet: empty_handle.clone(),
             sprite_number: 0,
             flip_horizontal: false,
             flip_vertical: false,
         })
         .with(Layered)
-        .with(top)
-        .with(Parent { entity: parent })
-        .with(GlobalTransform::default());
-    let top = if let Some(c) = table {
-        top.with(c).build()
+        .build()
     } else {
         top.build()
     };
 
     let side = world
         .create_entity()
-        .with(SpriteRender {
-            sprite_sheet: map_handle,
-            sprite_number: 7,
-            flip_horizontal: false,
-            flip_vertical: false,
-        })
         .with(Solid)
         .with(hitbox)
         .with(Interact {
             highlighted_by: None,
             top,
         })
-        .with(Layered)
         .with(side)
         .with(Parent { entity: parent })
         .with(GlobalTransform::default());
-    let side = if animate {
-        side.with(AnimatedTable(key, orientation)).build()
+    let side = if let Some((_, map_handle)) = handles {
+        let side = side
+            .with(SpriteRender {
+                sprite_sheet: map_handle,
+                sprite_number: 7,
+                flip_horizontal: false,
+                flip_vertical: false,
+            })
+            .with(Layered);
+        if animate {
+            side.with(AnimatedTable(key, orientation)).build()
+        } else {
+            side.build()
+        }
     } else {
         side.build()
     };
@@ -326,8 +326,10 @@ pub fn generate_ui(world: &mut World, entities: &mut Vec<Entity>, ui: &UiDefinit
                     let defs = world.read_resource::<Definitions>();
                     let anims = world.read_resource::<Animations>();
                     let anims = &anims.animations;
-                    let key = &defs.flavors().find(|x| x.index == *f).unwrap().key;
-                    let key = format!("{}_ball", key);
+                    let key = defs
+                        .get_flavor(f)
+                        .map(|x| format!("{}_ball", x.key))
+                        .unwrap_or_else(|| String::from("empty_item"));
                     info!("{}", key);
                     (anims[&key].obtain_handle(), anims[&key].get_frame())
                 };
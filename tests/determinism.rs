@@ -0,0 +1,92 @@
+use amethyst::utils::application_root_dir;
+use nk_data::*;
+use nk_loader::*;
+use nk_state::*;
+use std::iter;
+
+const SEED: u64 = 2018;
+const STEPS: usize = 60 * 60;
+
+fn simulation<'a, 'b>() -> Simulation<'a, 'b> {
+    let defs = load_game_data().expect("Failed to load game data!");
+    let map: MapDefinition = load_ron(
+        &format!("{}/assets/map/0001.ron", application_root_dir()),
+        "map definition",
+    )
+    .expect("Failed to load map!");
+
+    let flavors = defs
+        .flavors()
+        .take(4)
+        .fold(Vec::with_capacity(8), |mut acc, f| {
+            acc.push(f.index.clone());
+            acc.push(f.index.clone());
+            acc
+        });
+    let preparation = defs.preparations().next().unwrap().index.clone();
+    let topping = defs.toppings().next().unwrap().index.clone();
+
+    SimulationSetup::with_map(map)
+        .with_flavors(flavors)
+        .with_preparations(iter::repeat(preparation).take(4).collect())
+        .with_toppings(iter::repeat(topping).take(4).collect())
+        .with_seed(Some(SEED))
+        .build(defs)
+}
+
+fn orders(simulation: &Simulation) -> Vec<String> {
+    simulation
+        .world()
+        .read_resource::<Match>()
+        .teams
+        .iter()
+        .map(|t| format!("{:?}", t.orders))
+        .collect()
+}
+
+/// Walks each player around its kitchen, pressing interact and the face buttons now and then.
+/// Players are offset from each other so they do not all do the same thing at once.
+fn scripted_input(step: usize, player: usize) -> RecordedInput {
+    const MOVES: [Option<Cardinal>; 6] = [
+        Some(Cardinal::North),
+        Some(Cardinal::East),
+        None,
+        Some(Cardinal::SouthWest),
+        Some(Cardinal::West),
+        Some(Cardinal::South),
+    ];
+    let step = step + player * 17;
+    RecordedInput {
+        wants_to_move: MOVES[(step / 40) % MOVES.len()],
+        wants_to_interact: step % 50 < 2,
+        wants_north: step % 70 == 10,
+        wants_south: step % 70 == 30,
+        wants_west: step % 70 == 50,
+        wants_east: step % 70 == 60,
+        ..Default::default()
+    }
+}
+
+fn play(simulation: &mut Simulation) {
+    let players = simulation.players().to_vec();
+    for step in 0..STEPS {
+        for (i, player) in players.iter().enumerate() {
+            simulation.set_input(*player, &scripted_input(step, i));
+        }
+        simulation.step();
+    }
+}
+
+#[test]
+fn same_seed_plays_the_same_match() {
+    let mut first = simulation();
+    let mut second = simulation();
+    assert!(!first.players().is_empty());
+
+    play(&mut first);
+    play(&mut second);
+
+    assert!(orders(&first).iter().any(|o| o != "[]"));
+    assert_eq!(orders(&first), orders(&second));
+    assert_eq!(first.report(), second.report());
+}
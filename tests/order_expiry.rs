@@ -0,0 +1,71 @@
+use amethyst::{shrev::EventChannel, utils::application_root_dir};
+use nk_data::*;
+use nk_loader::*;
+use nk_state::*;
+
+const PENALTY: isize = 30;
+const ORDER_TIMER: f32 = 1.0;
+
+fn simulation<'a, 'b>() -> Simulation<'a, 'b> {
+    let defs = load_game_data().expect("Failed to load game data!");
+    let map: MapDefinition = load_ron(
+        &format!("{}/assets/map/0001.ron", application_root_dir()),
+        "map definition",
+    )
+    .expect("Failed to load map!");
+
+    let flavor = defs.flavors().next().unwrap().index.clone();
+    let preparation = defs.preparations().next().unwrap().index.clone();
+
+    let mut simulation = SimulationSetup::with_map(map)
+        .with_flavors(vec![flavor.clone()])
+        .with_preparations(vec![preparation.clone()])
+        .with_seed(Some(0))
+        .build(defs);
+
+    {
+        let mut match_data = simulation.world_mut().write_resource::<Match>();
+        // Only the orders requested below are in play.
+        match_data.generation.max_outstanding = 0;
+        match_data.generation.expiry_penalty = PENALTY;
+        for (team, score) in match_data.teams.iter_mut().zip(&[100, 10]) {
+            team.score = *score;
+            team.orders = vec![OrderDefinition::new(
+                Recipe::new(vec![flavor.clone()], vec![]),
+                preparation.clone(),
+                ORDER_TIMER,
+            )];
+        }
+    }
+    simulation
+}
+
+#[test]
+fn expired_orders_cost_at_most_the_current_score() {
+    let mut simulation = simulation();
+    let mut reader = simulation
+        .world_mut()
+        .write_resource::<EventChannel<OrderEvent>>()
+        .register_reader();
+
+    let steps = (ORDER_TIMER / TICK_DELTA) as usize + 2;
+    for _ in 0..steps {
+        simulation.step();
+    }
+
+    let expired = simulation
+        .world()
+        .read_resource::<EventChannel<OrderEvent>>()
+        .read(&mut reader)
+        .filter_map(|e| match e {
+            OrderEvent::Expired { team, penalty } => Some((*team, *penalty)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(expired, vec![(0, PENALTY), (1, 10)]);
+
+    assert_eq!(simulation.scores(), vec![100 - PENALTY, 0]);
+    let match_data = simulation.world().read_resource::<Match>();
+    assert!(match_data.teams.iter().all(|t| t.orders.is_empty()));
+    assert!(simulation.results().teams.iter().all(|t| t.expired == 1));
+}